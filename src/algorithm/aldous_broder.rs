@@ -0,0 +1,100 @@
+use super::Wilson;
+use crate::{Grid, MazeAlgorithm, Position};
+use rand::{rngs::SmallRng, seq::SliceRandom};
+use std::slice;
+
+/// Aldous-Broder algorithm, wandering randomly and carving into every unvisited cell it steps on.
+/// Samples uniformly from all the spanning trees of the grid, but takes a long time to find the last cells.
+#[derive(Debug)]
+pub struct AldousBroder {
+    cursor: Position,
+    visited: usize,
+    /// Number of cells reachable from the start
+    target: usize,
+}
+impl AldousBroder {
+    pub fn new(grid: &mut Grid, start: Position) -> Self {
+        grid.set_visited(start, true);
+        let target = grid.component(start).iter().filter(|&&cell| cell).count();
+        Self {
+            cursor: start,
+            visited: 1,
+            target,
+        }
+    }
+
+    /// Number of cells that are part of the maze
+    pub fn visited(&self) -> usize {
+        self.visited
+    }
+}
+impl MazeAlgorithm for AldousBroder {
+    fn step(&mut self, grid: &mut Grid, rng: &mut SmallRng) -> bool {
+        if self.visited >= self.target {
+            return true;
+        }
+
+        let dirs = grid
+            .directions()
+            .iter()
+            .copied()
+            .filter(|&dir| grid.neighbour(self.cursor, dir).is_some())
+            .collect::<Vec<_>>();
+        let dir = *dirs.choose(rng).unwrap();
+        let next = grid.neighbour(self.cursor, dir).unwrap();
+        if !grid.visited(next) {
+            grid.carve(self.cursor, dir);
+            grid.set_visited(next, true);
+            self.visited += 1;
+        }
+        self.cursor = next;
+
+        self.visited >= self.target
+    }
+
+    fn active(&self) -> &[Position] {
+        slice::from_ref(&self.cursor)
+    }
+}
+
+/// Runs Aldous-Broder until a fraction of the cells are visited, then finishes with Wilson's algorithm.
+/// Still uniform, while avoiding the slow start of Wilson's and the slow end of Aldous-Broder.
+#[derive(Debug)]
+pub struct AldousBroderWilson {
+    start: Position,
+    aldous_broder: AldousBroder,
+    wilson: Option<Wilson>,
+    switch_at: usize,
+}
+impl AldousBroderWilson {
+    pub fn new(grid: &mut Grid, start: Position, fraction: f64) -> Self {
+        let fraction = fraction.clamp(0.0, 1.0);
+        let aldous_broder = AldousBroder::new(grid, start);
+        let switch_at = (aldous_broder.target as f64 * fraction).ceil() as usize;
+        Self {
+            start,
+            aldous_broder,
+            wilson: None,
+            switch_at,
+        }
+    }
+}
+impl MazeAlgorithm for AldousBroderWilson {
+    fn step(&mut self, grid: &mut Grid, rng: &mut SmallRng) -> bool {
+        if let Some(wilson) = &mut self.wilson {
+            return wilson.step(grid, rng);
+        }
+        if self.aldous_broder.visited() < self.switch_at {
+            return self.aldous_broder.step(grid, rng);
+        }
+        self.wilson = Some(Wilson::from_component(grid, self.start, rng));
+        false
+    }
+
+    fn active(&self) -> &[Position] {
+        match &self.wilson {
+            Some(wilson) => wilson.active(),
+            None => self.aldous_broder.active(),
+        }
+    }
+}
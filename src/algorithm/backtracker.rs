@@ -0,0 +1,93 @@
+use super::DirectionWeights;
+use crate::{Direction, Grid, MazeAlgorithm, Position};
+use rand::rngs::SmallRng;
+
+/// Randomized depth-first search, carving long corridors and backtracking on dead ends.
+/// When weaving, it can also tunnel under a straight perpendicular corridor to reach the
+/// unvisited cell behind it, turning the corridor cell into a crossing.
+#[derive(Debug)]
+pub struct RecursiveBacktracker {
+    cursor: Position,
+    tail: Vec<Position>,
+    weights: DirectionWeights,
+    last: Option<Direction>,
+    weave: bool,
+}
+impl RecursiveBacktracker {
+    pub fn new(grid: &mut Grid, start: Position) -> Self {
+        grid.set_visited(start, true);
+        Self {
+            cursor: start,
+            tail: vec![start],
+            weights: DirectionWeights::UNIFORM,
+            last: None,
+            weave: false,
+        }
+    }
+
+    pub fn with_weights(mut self, weights: DirectionWeights) -> Self {
+        self.weights = weights;
+        self
+    }
+
+    pub fn with_weave(mut self, weave: bool) -> Self {
+        self.weave = weave;
+        self
+    }
+
+    /// Returns the cell to tunnel under and the unvisited cell behind it, if `pos` can hop over its
+    /// neighbour in the given direction
+    fn hop(grid: &Grid, pos: Position, dir: Direction) -> Option<(Position, Position)> {
+        if grid.cell(pos).crossing() {
+            return None;
+        }
+        let under = grid.neighbour(pos, dir)?;
+        let straight = grid.is_straight_across(under, dir);
+        if !grid.visited(under) || grid.cell(under).crossing() || !straight {
+            return None;
+        }
+        let next = grid.neighbour(under, dir)?;
+        if grid.visited(next) {
+            None
+        } else {
+            Some((under, next))
+        }
+    }
+}
+impl MazeAlgorithm for RecursiveBacktracker {
+    fn step(&mut self, grid: &mut Grid, rng: &mut SmallRng) -> bool {
+        let next_pos = self.weights.order(rng, grid.directions(), self.last);
+        let dest =
+            next_pos
+                .iter()
+                .copied()
+                .find_map(|dir| match grid.neighbour(self.cursor, dir) {
+                    Some(pos) if !grid.visited(pos) => Some((dir, pos, None)),
+                    _ if self.weave => Self::hop(grid, self.cursor, dir)
+                        .map(|(under, pos)| (dir, pos, Some(under))),
+                    _ => None,
+                });
+
+        match dest {
+            Some((dir, next, under)) => {
+                match under {
+                    Some(under) => grid.cell_mut(under).set_crossing(true),
+                    None => grid.carve(self.cursor, dir),
+                }
+                self.cursor = next;
+                grid.set_visited(self.cursor, true);
+                self.tail.push(self.cursor);
+                self.last = Some(dir);
+            }
+            None => match self.tail.pop() {
+                None => return true,
+                Some(pos) => {
+                    self.cursor = pos;
+                    self.last = None;
+                }
+            },
+        }
+
+        false
+    }
+}
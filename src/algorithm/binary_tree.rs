@@ -0,0 +1,44 @@
+use super::Bias;
+use crate::{Grid, MazeAlgorithm, Position};
+use rand::{rngs::SmallRng, Rng};
+
+/// Binary tree algorithm, carving each cell towards one of the two sides of the bias corner.
+/// Needs no memory besides the current cell, but leaves two long corridors along the bias sides.
+#[derive(Debug)]
+pub struct BinaryTree {
+    bias: Bias,
+    index: usize,
+}
+impl BinaryTree {
+    pub fn new(bias: Bias) -> Self {
+        Self { bias, index: 0 }
+    }
+}
+impl MazeAlgorithm for BinaryTree {
+    fn step(&mut self, grid: &mut Grid, rng: &mut SmallRng) -> bool {
+        if self.index >= grid.len() {
+            return true;
+        }
+        let pos = Position::new(self.index % grid.width(), self.index / grid.width());
+        self.index += 1;
+
+        let vertical = self.bias.vertical();
+        let horizontal = self.bias.horizontal();
+        let dir = match (
+            grid.neighbour(pos, vertical).is_some(),
+            grid.neighbour(pos, horizontal).is_some(),
+        ) {
+            (true, true) if rng.gen() => Some(vertical),
+            (true, true) => Some(horizontal),
+            (true, false) => Some(vertical),
+            (false, true) => Some(horizontal),
+            (false, false) => None,
+        };
+        if let Some(dir) = dir {
+            grid.carve(pos, dir);
+        }
+        grid.set_visited(pos, true);
+
+        self.index >= grid.len()
+    }
+}
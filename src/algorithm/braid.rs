@@ -0,0 +1,62 @@
+use crate::{Direction, Grid, Position};
+use rand::{rngs::SmallRng, seq::SliceRandom};
+
+/// Whether the cell is part of the maze and has a single way out
+pub fn is_dead_end(grid: &Grid, pos: Position) -> bool {
+    let openings = grid
+        .directions()
+        .iter()
+        .filter(|&&dir| grid.passage(pos, dir).is_some())
+        .count();
+    grid.visited(pos) && openings == 1
+}
+
+/// Removes a fraction of the dead ends by knocking out one of their walls, creating loops.
+/// Walls leading to another dead end are knocked out first, fixing both dead ends at once.
+pub fn braid(grid: &mut Grid, rng: &mut SmallRng, factor: f64) {
+    let mut dead_ends = vec![];
+    for pos in grid.positions() {
+        if is_dead_end(grid, pos) {
+            dead_ends.push(pos);
+        }
+    }
+    dead_ends.shuffle(rng);
+
+    let target = (dead_ends.len() as f64 * factor.clamp(0.0, 1.0)).round() as usize;
+    let mut removed = 0;
+    for pos in dead_ends {
+        if removed >= target {
+            break;
+        }
+        if !is_dead_end(grid, pos) {
+            continue;
+        }
+
+        let mut candidates = grid
+            .directions()
+            .iter()
+            .copied()
+            .filter(|&dir| {
+                grid.passage(pos, dir).is_none()
+                    && grid
+                        .neighbour(pos, dir)
+                        .is_some_and(|next| grid.visited(next) && !grid.cell(next).crossing())
+            })
+            .collect::<Vec<_>>();
+        candidates.shuffle(rng);
+        let joins_dead_end =
+            |&dir: &Direction| is_dead_end(grid, grid.neighbour(pos, dir).unwrap());
+        let dir = match candidates.iter().find(|dir| joins_dead_end(dir)) {
+            Some(&dir) => {
+                removed += 1;
+                dir
+            }
+            None => match candidates.first() {
+                Some(&dir) => dir,
+                None => continue,
+            },
+        };
+        grid.carve(pos, dir);
+        removed += 1;
+    }
+}
@@ -0,0 +1,135 @@
+use crate::{Direction, Grid, MazeAlgorithm, Position};
+use rand::{rngs::SmallRng, Rng};
+use wasm_bindgen::prelude::*;
+
+/// Life-like rules, where living blocks are solid rock and dead blocks are floor
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AutomatonRule {
+    /// B3/S12345, grows twisty maze-like corridors
+    Maze,
+    /// B3/S1234, like `Maze` with longer straight corridors
+    Mazectric,
+    /// B5678/S45678, smooths random noise into open caves
+    Cave,
+}
+impl AutomatonRule {
+    /// Bitmasks of the neighbour counts for which a block is born and survives
+    fn masks(self) -> (u16, u16) {
+        match self {
+            AutomatonRule::Maze => (0b1000, 0b11_1110),
+            AutomatonRule::Mazectric => (0b1000, 0b1_1110),
+            AutomatonRule::Cave => (0b1_1110_0000, 0b1_1111_0000),
+        }
+    }
+
+    /// Whether the outside of the grid counts as rock
+    fn solid_border(self) -> bool {
+        self == AutomatonRule::Cave
+    }
+}
+
+/// Runs a cellular automaton on a grid of blocks filled with random noise, one generation per step,
+/// and converts the blocks to walls: floor blocks are visited cells with passages between them,
+/// rock blocks are unvisited cells walled on every side. The result is usually not a perfect maze
+/// and its floor is not guaranteed to be connected.
+#[derive(Debug)]
+pub struct CellularAutomaton {
+    rule: AutomatonRule,
+    alive: Vec<bool>,
+    next: Vec<bool>,
+    generations: usize,
+}
+impl CellularAutomaton {
+    pub fn new(
+        grid: &mut Grid,
+        rng: &mut SmallRng,
+        rule: AutomatonRule,
+        fill: f64,
+        generations: usize,
+    ) -> Self {
+        let fill = fill.clamp(0.0, 1.0);
+        let alive = (0..grid.len())
+            .map(|_| rng.gen_bool(fill))
+            .collect::<Vec<_>>();
+        let this = Self {
+            rule,
+            next: alive.clone(),
+            alive,
+            generations,
+        };
+        this.apply(grid);
+        this
+    }
+
+    fn alive_neighbours(&self, grid: &Grid, x: usize, y: usize) -> u32 {
+        let mut count = 0;
+        for dy in -1..=1_isize {
+            for dx in -1..=1_isize {
+                if dx == 0 && dy == 0 {
+                    continue;
+                }
+                let nx = x as isize + dx;
+                let ny = y as isize + dy;
+                let inside = nx >= 0
+                    && ny >= 0
+                    && (nx as usize) < grid.width()
+                    && (ny as usize) < grid.height();
+                let alive = if inside {
+                    self.alive[grid.get_cell_offset(nx as usize, ny as usize)]
+                } else {
+                    self.rule.solid_border()
+                };
+                count += alive as u32;
+            }
+        }
+        count
+    }
+
+    /// Converts the blocks to the walls of the grid
+    fn apply(&self, grid: &mut Grid) {
+        for y in 0..grid.height() {
+            for x in 0..grid.width() {
+                let pos = Position::new(x, y);
+                let alive = self.alive[grid.get_cell_offset(x, y)];
+                grid.set_visited(pos, !alive);
+                for &dir in [Direction::Right, Direction::Bottom].iter() {
+                    let wall = match grid.neighbour(pos, dir) {
+                        Some(next) => alive || self.alive[grid.get_cell_offset(next.x, next.y)],
+                        None => true,
+                    };
+                    grid.set_wall(pos, dir, wall);
+                }
+            }
+        }
+    }
+}
+impl MazeAlgorithm for CellularAutomaton {
+    fn step(&mut self, grid: &mut Grid, _rng: &mut SmallRng) -> bool {
+        if self.generations == 0 {
+            return true;
+        }
+        self.generations -= 1;
+
+        let (birth, survival) = self.rule.masks();
+        for y in 0..grid.height() {
+            for x in 0..grid.width() {
+                let offset = grid.get_cell_offset(x, y);
+                let mask = 1 << self.alive_neighbours(grid, x, y);
+                self.next[offset] = if self.alive[offset] {
+                    survival & mask != 0
+                } else {
+                    birth & mask != 0
+                };
+            }
+        }
+        let stable = self.alive == self.next;
+        std::mem::swap(&mut self.alive, &mut self.next);
+        self.apply(grid);
+
+        if stable {
+            self.generations = 0;
+        }
+        self.generations == 0
+    }
+}
@@ -0,0 +1,89 @@
+use crate::Direction;
+use rand::{rngs::SmallRng, seq::SliceRandom, Rng};
+
+/// Relative weights used to pick the direction to carve towards
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DirectionWeights {
+    pub horizontal: f64,
+    pub vertical: f64,
+    /// Multiplies the weight of continuing straight by `1 + straightness`,
+    /// negative values down to -1 make the corridors twistier
+    pub straightness: f64,
+    /// Weight of carving to another layer, in 3D grids
+    pub layer: f64,
+}
+impl DirectionWeights {
+    pub const UNIFORM: Self = Self::new(1.0, 1.0, 0.0);
+
+    pub const fn new(horizontal: f64, vertical: f64, straightness: f64) -> Self {
+        Self {
+            horizontal,
+            vertical,
+            straightness,
+            layer: 1.0,
+        }
+    }
+
+    pub fn with_layer(mut self, layer: f64) -> Self {
+        self.layer = layer;
+        self
+    }
+
+    fn weight(&self, dir: Direction, last: Option<Direction>) -> f64 {
+        let weight = match dir {
+            Direction::Left | Direction::Right => self.horizontal,
+            Direction::Top | Direction::Bottom => self.vertical,
+            Direction::Up | Direction::Down => self.layer,
+        };
+        let weight = if last == Some(dir) {
+            weight * (1.0 + self.straightness)
+        } else {
+            weight
+        };
+        weight.max(0.0)
+    }
+
+    /// Returns `dirs` in the order they should be tried, drawn by weight
+    /// without replacement; directions with no weight come last in a random order.
+    /// `last` is the direction the cursor arrived from, if any.
+    pub fn order(
+        &self,
+        rng: &mut SmallRng,
+        dirs: &[Direction],
+        last: Option<Direction>,
+    ) -> Vec<Direction> {
+        let mut dirs = dirs.to_vec();
+        dirs.shuffle(rng);
+        if *self == Self::UNIFORM {
+            return dirs;
+        }
+
+        let mut weights = dirs
+            .iter()
+            .map(|&dir| self.weight(dir, last))
+            .collect::<Vec<_>>();
+        for i in 0..dirs.len() {
+            let total: f64 = weights[i..].iter().sum();
+            if total <= 0.0 {
+                break;
+            }
+            let mut roll = rng.gen_range(0.0, total);
+            let mut picked = dirs.len() - 1;
+            for (j, &weight) in weights.iter().enumerate().skip(i) {
+                if weight > 0.0 && roll < weight {
+                    picked = j;
+                    break;
+                }
+                roll -= weight;
+            }
+            dirs.swap(i, picked);
+            weights.swap(i, picked);
+        }
+        dirs
+    }
+}
+impl Default for DirectionWeights {
+    fn default() -> Self {
+        Self::UNIFORM
+    }
+}
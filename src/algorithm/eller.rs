@@ -0,0 +1,147 @@
+use crate::MazeCell;
+use rand::{rngs::SmallRng, Rng, SeedableRng};
+use wasm_bindgen::prelude::*;
+
+const NO_SET: u32 = u32::MAX;
+
+/// Eller's algorithm, streaming an endless maze one row at a time using O(width) memory.
+/// Each row gets its own rng derived from the seed and the row index, so the state
+/// between two rows is only the row index and the sets carried down from the previous row.
+#[wasm_bindgen]
+#[derive(Clone, Debug)]
+pub struct EllerStream {
+    seed: u64,
+    row_index: usize,
+    carry: Vec<u32>,
+    row: Vec<MazeCell>,
+}
+#[wasm_bindgen]
+impl EllerStream {
+    pub fn new(width: usize, seed: u64) -> Self {
+        Self {
+            seed,
+            row_index: 0,
+            carry: vec![NO_SET; width],
+            row: vec![],
+        }
+    }
+    /// Resumes a stream from the row index and the state returned by `state`. Returns `None` if
+    /// the state doesn't have one set per column or holds a set that doesn't exist.
+    pub fn from_state(
+        seed: u64,
+        row_index: usize,
+        width: usize,
+        state: &[u32],
+    ) -> Option<EllerStream> {
+        let valid = |&set: &u32| set == NO_SET || (set as usize) < width;
+        if state.len() != width || !state.iter().all(valid) {
+            return None;
+        }
+        Some(Self {
+            seed,
+            row_index,
+            carry: state.to_vec(),
+            row: vec![],
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.carry.len()
+    }
+    /// Index of the next row to be generated
+    pub fn row_index(&self) -> usize {
+        self.row_index
+    }
+    /// Sets carried down to the next row, `u32::MAX` for cells not connected to the row above
+    pub fn state(&self) -> Vec<u32> {
+        self.carry.clone()
+    }
+
+    pub fn row_ptr(&self) -> *const MazeCell {
+        self.row.as_ptr()
+    }
+    pub fn get_cell(&self, x: usize) -> MazeCell {
+        self.row[x]
+    }
+
+    /// Generates the next row, leaving passages down to the following one
+    pub fn next_row(&mut self) {
+        self.gen_row(false);
+    }
+    /// Generates a row closing the maze, the stream starts a new disconnected maze afterwards
+    pub fn last_row(&mut self) {
+        self.gen_row(true);
+    }
+}
+impl EllerStream {
+    pub fn row(&self) -> &[MazeCell] {
+        &self.row
+    }
+
+    fn gen_row(&mut self, last: bool) {
+        let width = self.width();
+        let mut rng = SmallRng::seed_from_u64(
+            self.seed ^ (self.row_index as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15),
+        );
+
+        // Cells without a passage from above start in their own set
+        let mut used = vec![false; width];
+        for &set in self.carry.iter().filter(|&&set| set != NO_SET) {
+            used[set as usize] = true;
+        }
+        let mut fresh = (0..width as u32).filter(|&set| !used[set as usize]);
+        let mut sets = self
+            .carry
+            .iter()
+            .map(|&set| match set {
+                NO_SET => fresh.next().unwrap(),
+                set => set,
+            })
+            .collect::<Vec<_>>();
+
+        let mut default_cell = MazeCell::new();
+        default_cell.set_visited(true);
+        default_cell.set_right(true);
+        default_cell.set_bottom(true);
+        self.row.clear();
+        self.row.resize(width, default_cell);
+
+        for x in 0..width.saturating_sub(1) {
+            if sets[x] != sets[x + 1] && (last || rng.gen_bool(0.5)) {
+                self.row[x].set_right(false);
+                let (from, to) = (sets[x + 1], sets[x]);
+                for set in sets.iter_mut().filter(|set| **set == from) {
+                    *set = to;
+                }
+            }
+        }
+
+        self.carry.iter_mut().for_each(|set| *set = NO_SET);
+        self.row_index += 1;
+        if last {
+            return;
+        }
+
+        // Every set must continue at least once into the next row
+        let mut members = vec![vec![]; width];
+        for (x, &set) in sets.iter().enumerate() {
+            members[set as usize].push(x);
+        }
+        let mut renamed = vec![NO_SET; width];
+        let mut next_set = 0;
+        for columns in members.iter().filter(|columns| !columns.is_empty()) {
+            let forced = columns[rng.gen_range(0, columns.len())];
+            for &x in columns.iter() {
+                if x == forced || rng.gen_bool(0.5) {
+                    self.row[x].set_bottom(false);
+                    let set = sets[x] as usize;
+                    if renamed[set] == NO_SET {
+                        renamed[set] = next_set;
+                        next_set += 1;
+                    }
+                    self.carry[x] = renamed[set];
+                }
+            }
+        }
+    }
+}
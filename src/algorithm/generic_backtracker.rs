@@ -0,0 +1,41 @@
+use crate::{MazeAlgorithm, Topology};
+use rand::{rngs::SmallRng, seq::SliceRandom};
+
+/// Randomized depth-first search working on any `Topology`
+#[derive(Debug)]
+pub struct GenericBacktracker<C> {
+    tail: Vec<C>,
+}
+impl<C: Copy> GenericBacktracker<C> {
+    pub fn new<G: Topology<Cell = C>>(grid: &mut G, start: C) -> Self {
+        grid.set_visited(start, true);
+        Self { tail: vec![start] }
+    }
+}
+impl<G: Topology> MazeAlgorithm<G> for GenericBacktracker<G::Cell> {
+    fn step(&mut self, grid: &mut G, rng: &mut SmallRng) -> bool {
+        let cursor = match self.tail.last() {
+            Some(&cursor) => cursor,
+            None => return true,
+        };
+
+        let mut neighbours = grid.neighbours(cursor);
+        neighbours.shuffle(rng);
+        match neighbours.into_iter().find(|&next| !grid.visited(next)) {
+            Some(next) => {
+                grid.link(cursor, next);
+                grid.set_visited(next, true);
+                self.tail.push(next);
+            }
+            None => {
+                self.tail.pop();
+            }
+        }
+
+        self.tail.is_empty()
+    }
+
+    fn active(&self) -> &[G::Cell] {
+        &self.tail
+    }
+}
@@ -0,0 +1,41 @@
+use super::DisjointSet;
+use crate::{MazeAlgorithm, Topology};
+use rand::{rngs::SmallRng, seq::SliceRandom};
+
+/// Randomized Kruskal's algorithm working on any `Topology`
+#[derive(Debug)]
+pub struct GenericKruskal<C> {
+    walls: Vec<(C, C)>,
+    sets: DisjointSet,
+}
+impl<C: Copy> GenericKruskal<C> {
+    pub fn new<G: Topology<Cell = C>>(grid: &mut G, rng: &mut SmallRng) -> Self {
+        let mut walls = vec![];
+        for index in 0..grid.len() {
+            let cell = grid.cell(index);
+            for next in grid.neighbours(cell) {
+                if grid.index(next) > index {
+                    walls.push((cell, next));
+                }
+            }
+        }
+        walls.shuffle(rng);
+        Self {
+            walls,
+            sets: DisjointSet::new(grid.len()),
+        }
+    }
+}
+impl<G: Topology> MazeAlgorithm<G> for GenericKruskal<G::Cell> {
+    fn step(&mut self, grid: &mut G, _rng: &mut SmallRng) -> bool {
+        while let Some((a, b)) = self.walls.pop() {
+            if self.sets.union(grid.index(a), grid.index(b)) {
+                grid.link(a, b);
+                grid.set_visited(a, true);
+                grid.set_visited(b, true);
+                return self.walls.is_empty();
+            }
+        }
+        true
+    }
+}
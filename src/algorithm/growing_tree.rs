@@ -0,0 +1,144 @@
+use super::DirectionWeights;
+use crate::{Direction, Grid, MazeAlgorithm, Position};
+use rand::{rngs::SmallRng, Rng};
+
+/// How the growing tree picks the active cell to grow from, as relative weights
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SelectionPolicy {
+    pub newest: f64,
+    pub random: f64,
+    pub oldest: f64,
+}
+impl SelectionPolicy {
+    /// Always grows from the newest cell, like the recursive backtracker
+    pub const NEWEST: Self = Self::new(1.0, 0.0, 0.0);
+    /// Always grows from a random cell, with a texture close to Prim's
+    pub const RANDOM: Self = Self::new(0.0, 1.0, 0.0);
+    pub const OLDEST: Self = Self::new(0.0, 0.0, 1.0);
+
+    pub const fn new(newest: f64, random: f64, oldest: f64) -> Self {
+        Self {
+            newest,
+            random,
+            oldest,
+        }
+    }
+
+    /// Whether the policy always picks a random cell, regardless of the order of the cells
+    fn is_random(&self) -> bool {
+        self.random > 0.0 && self.newest <= 0.0 && self.oldest <= 0.0
+    }
+
+    fn select(&self, len: usize, rng: &mut SmallRng) -> usize {
+        let newest = self.newest.max(0.0);
+        let random = self.random.max(0.0);
+        let oldest = self.oldest.max(0.0);
+        let total = newest + random + oldest;
+        if total <= 0.0 {
+            return len - 1;
+        }
+
+        let roll = if newest == total || random == total || oldest == total {
+            0.0
+        } else {
+            rng.gen_range(0.0, total)
+        };
+        if newest > 0.0 && roll < newest {
+            len - 1
+        } else if random > 0.0 && roll < newest + random {
+            rng.gen_range(0, len)
+        } else {
+            0
+        }
+    }
+}
+impl Default for SelectionPolicy {
+    fn default() -> Self {
+        Self::NEWEST
+    }
+}
+
+/// Growing tree algorithm, growing the maze from a list of active cells chosen by a `SelectionPolicy`
+#[derive(Debug)]
+pub struct GrowingTree {
+    policy: SelectionPolicy,
+    /// Active cells from oldest to newest, starting at `head`
+    cells: Vec<Position>,
+    head: usize,
+    weights: DirectionWeights,
+    /// The cell carved into by the last step, and the direction it was entered from
+    last: Option<(Position, Direction)>,
+}
+impl GrowingTree {
+    pub fn new(grid: &mut Grid, start: Position, policy: SelectionPolicy) -> Self {
+        grid.set_visited(start, true);
+        Self {
+            policy,
+            cells: vec![start],
+            head: 0,
+            weights: DirectionWeights::UNIFORM,
+            last: None,
+        }
+    }
+
+    pub fn with_weights(mut self, weights: DirectionWeights) -> Self {
+        self.weights = weights;
+        self
+    }
+
+    /// Removes an active cell and keeps the others from oldest to newest, by shifting whichever
+    /// side of it is shorter. The cells before the head are only dropped once they outnumber the
+    /// active ones, and a purely random policy doesn't need the order so the newest cell simply
+    /// takes the place of the removed one.
+    fn remove(&mut self, index: usize) {
+        let index = self.head + index;
+        if self.policy.is_random() {
+            self.cells.swap_remove(index);
+        } else if index - self.head < self.cells.len() - index {
+            self.cells.copy_within(self.head..index, self.head + 1);
+            self.head += 1;
+        } else {
+            self.cells.remove(index);
+        }
+        if self.head * 2 >= self.cells.len() {
+            self.cells.drain(..self.head);
+            self.head = 0;
+        }
+    }
+}
+impl MazeAlgorithm for GrowingTree {
+    fn step(&mut self, grid: &mut Grid, rng: &mut SmallRng) -> bool {
+        if self.active().is_empty() {
+            return true;
+        }
+        let index = self.policy.select(self.active().len(), rng);
+        let pos = self.active()[index];
+
+        let last = self
+            .last
+            .take()
+            .filter(|&(last, _)| last == pos)
+            .map(|(_, dir)| dir);
+        let dirs = self.weights.order(rng, grid.directions(), last);
+        let dest = dirs.iter().copied().find(|&dir| {
+            grid.neighbour(pos, dir)
+                .is_some_and(|next| !grid.visited(next))
+        });
+        match dest {
+            Some(dir) => {
+                let next = grid.neighbour(pos, dir).unwrap();
+                grid.carve(pos, dir);
+                grid.set_visited(next, true);
+                self.cells.push(next);
+                self.last = Some((next, dir));
+            }
+            None => self.remove(index),
+        }
+
+        self.active().is_empty()
+    }
+
+    fn active(&self) -> &[Position] {
+        &self.cells[self.head..]
+    }
+}
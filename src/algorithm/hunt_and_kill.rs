@@ -0,0 +1,115 @@
+use super::DirectionWeights;
+use crate::{Direction, Grid, MazeAlgorithm, Position};
+use rand::{rngs::SmallRng, seq::SliceRandom};
+use std::slice;
+
+/// Hunt-and-kill algorithm, walking randomly into unvisited cells like the recursive backtracker,
+/// but on a dead end it scans the rows (one per step) for an unvisited cell next to the maze
+/// instead of backtracking.
+///
+/// The recursive backtracker keeps every cell of the current path in its `tail`, which can grow
+/// up to `width * height * depth` positions (12 bytes each on wasm32). Hunt-and-kill only keeps the
+/// cursor and two row indices, at the cost of rescanning rows: the scans skip the rows known to be
+/// complete, but still make it O((width * height * depth) * height * depth) in the worst case.
+/// The rows of every layer are scanned one after the other.
+#[derive(Debug)]
+pub struct HuntAndKill {
+    cursor: Option<Position>,
+    scan_row: usize,
+    first_incomplete_row: usize,
+    weights: DirectionWeights,
+    last: Option<Direction>,
+}
+impl HuntAndKill {
+    pub fn new(grid: &mut Grid, start: Position) -> Self {
+        grid.set_visited(start, true);
+        Self {
+            cursor: Some(start),
+            scan_row: 0,
+            first_incomplete_row: 0,
+            weights: DirectionWeights::UNIFORM,
+            last: None,
+        }
+    }
+
+    pub fn with_weights(mut self, weights: DirectionWeights) -> Self {
+        self.weights = weights;
+        self
+    }
+
+    /// Scans the current row for an unvisited cell next to the maze and connects it
+    fn hunt(&mut self, grid: &mut Grid, rng: &mut SmallRng) -> bool {
+        let mut complete = true;
+        for x in 0..grid.width() {
+            let pos = Position::new_3d(
+                x,
+                self.scan_row % grid.height(),
+                self.scan_row / grid.height(),
+            );
+            if grid.visited(pos) {
+                continue;
+            }
+            complete = false;
+
+            let dirs = grid
+                .directions()
+                .iter()
+                .copied()
+                .filter(|&dir| {
+                    grid.neighbour(pos, dir)
+                        .is_some_and(|next| grid.visited(next))
+                })
+                .collect::<Vec<_>>();
+            if let Some(&dir) = dirs.choose(rng) {
+                grid.carve(pos, dir);
+                grid.set_visited(pos, true);
+                self.cursor = Some(pos);
+                self.last = None;
+                return false;
+            }
+        }
+
+        if complete && self.scan_row == self.first_incomplete_row {
+            self.first_incomplete_row += 1;
+        }
+        self.scan_row += 1;
+        self.scan_row >= grid.height() * grid.depth()
+    }
+}
+impl MazeAlgorithm for HuntAndKill {
+    fn step(&mut self, grid: &mut Grid, rng: &mut SmallRng) -> bool {
+        let cursor = match self.cursor {
+            Some(cursor) => cursor,
+            None if self.scan_row >= grid.height() * grid.depth() => return true,
+            None => return self.hunt(grid, rng),
+        };
+
+        let dirs = self.weights.order(rng, grid.directions(), self.last);
+        let dest = dirs.iter().copied().find(|&dir| {
+            grid.neighbour(cursor, dir)
+                .is_some_and(|next| !grid.visited(next))
+        });
+        match dest {
+            Some(dir) => {
+                let next = grid.neighbour(cursor, dir).unwrap();
+                grid.carve(cursor, dir);
+                grid.set_visited(next, true);
+                self.cursor = Some(next);
+                self.last = Some(dir);
+            }
+            None => {
+                self.cursor = None;
+                self.scan_row = self.first_incomplete_row;
+            }
+        }
+
+        false
+    }
+
+    fn active(&self) -> &[Position] {
+        match &self.cursor {
+            Some(cursor) => slice::from_ref(cursor),
+            None => &[],
+        }
+    }
+}
@@ -0,0 +1,43 @@
+use super::DisjointSet;
+use crate::{Direction, Grid, MazeAlgorithm, Position};
+use rand::{rngs::SmallRng, seq::SliceRandom};
+
+/// Randomized Kruskal's algorithm, removing walls in random order whenever they join two separate trees
+#[derive(Debug)]
+pub struct Kruskal {
+    walls: Vec<(Position, Direction)>,
+    sets: DisjointSet,
+}
+impl Kruskal {
+    pub fn new(grid: &mut Grid, rng: &mut SmallRng) -> Self {
+        let mut walls = vec![];
+        for pos in grid.positions() {
+            for &dir in grid.forward_directions() {
+                if grid.neighbour(pos, dir).is_some() {
+                    walls.push((pos, dir));
+                }
+            }
+        }
+        walls.shuffle(rng);
+        Self {
+            walls,
+            sets: DisjointSet::new(grid.len()),
+        }
+    }
+}
+impl MazeAlgorithm for Kruskal {
+    fn step(&mut self, grid: &mut Grid, _rng: &mut SmallRng) -> bool {
+        while let Some((pos, dir)) = self.walls.pop() {
+            let next = grid.neighbour(pos, dir).unwrap();
+            let a = grid.offset(pos);
+            let b = grid.offset(next);
+            if self.sets.union(a, b) {
+                grid.carve(pos, dir);
+                grid.set_visited(pos, true);
+                grid.set_visited(next, true);
+                return self.walls.is_empty();
+            }
+        }
+        true
+    }
+}
@@ -0,0 +1,407 @@
+mod aldous_broder;
+mod backtracker;
+mod binary_tree;
+mod braid;
+mod cellular_automaton;
+mod direction_weights;
+mod disjoint_set;
+mod eller;
+mod generic_backtracker;
+mod generic_kruskal;
+mod growing_tree;
+mod hunt_and_kill;
+mod kruskal;
+mod multi_seed;
+mod origin_shift;
+mod prim;
+mod recursive_division;
+mod sidewinder;
+mod wilson;
+pub use aldous_broder::*;
+pub use backtracker::*;
+pub use binary_tree::*;
+pub use braid::*;
+pub use cellular_automaton::*;
+pub use direction_weights::*;
+pub(crate) use disjoint_set::*;
+pub use eller::*;
+pub use generic_backtracker::*;
+pub use generic_kruskal::*;
+pub use growing_tree::*;
+pub use hunt_and_kill::*;
+pub use kruskal::*;
+pub use multi_seed::*;
+pub use origin_shift::*;
+pub use prim::*;
+pub use recursive_division::*;
+pub use sidewinder::*;
+pub use wilson::*;
+
+use crate::{Direction, Grid, Position, Topology, Wrap};
+use rand::rngs::SmallRng;
+use std::fmt;
+use wasm_bindgen::prelude::*;
+
+/// A maze generation algorithm that can be driven one step at a time, on the square `Grid`
+/// unless stated otherwise
+pub trait MazeAlgorithm<G: Topology = Grid>: fmt::Debug {
+    /// Runs a single generation step, returns true once the maze is complete
+    fn step(&mut self, grid: &mut G, rng: &mut SmallRng) -> bool;
+
+    /// Runs the remaining steps until the maze is complete
+    fn finish(&mut self, grid: &mut G, rng: &mut SmallRng) {
+        while !self.step(grid, rng) {}
+    }
+
+    /// Cells the algorithm is currently working on, for example the random walk of Wilson's
+    fn active(&self) -> &[G::Cell] {
+        &[]
+    }
+}
+
+/// Algorithm for a topology that has nothing to carve, complete from the start
+#[derive(Debug)]
+pub struct Finished;
+impl<G: Topology> MazeAlgorithm<G> for Finished {
+    fn step(&mut self, _grid: &mut G, _rng: &mut SmallRng) -> bool {
+        true
+    }
+}
+
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Algorithm {
+    RecursiveBacktracker,
+    Prim,
+    Kruskal,
+    Wilson,
+    AldousBroder,
+    AldousBroderWilson,
+    GrowingTree,
+    RecursiveDivision,
+    BinaryTree,
+    Sidewinder,
+    HuntAndKill,
+    CellularAutomaton,
+}
+impl Algorithm {
+    /// Whether the algorithm can grow several trees at once, see `build_with_starts`
+    pub fn supports_multiple_starts(self) -> bool {
+        matches!(
+            self,
+            Algorithm::RecursiveBacktracker
+                | Algorithm::Prim
+                | Algorithm::GrowingTree
+                | Algorithm::HuntAndKill
+        )
+    }
+
+    /// Whether the algorithm can carve grids of several layers, the others only work on flat grids
+    /// and fall back to the recursive backtracker on 3D ones
+    pub fn supports_3d(self) -> bool {
+        !matches!(
+            self,
+            Algorithm::RecursiveDivision
+                | Algorithm::BinaryTree
+                | Algorithm::Sidewinder
+                | Algorithm::CellularAutomaton
+        )
+    }
+
+    /// Whether the algorithm can carve passages across wrapping borders, the others only work
+    /// within the rectangle of the grid and fall back to the recursive backtracker when it wraps
+    pub fn supports_wrap(self) -> bool {
+        !matches!(
+            self,
+            Algorithm::RecursiveDivision
+                | Algorithm::BinaryTree
+                | Algorithm::Sidewinder
+                | Algorithm::CellularAutomaton
+        )
+    }
+
+    /// Whether the algorithm can carve around void cells, the others fall back to the recursive
+    /// backtracker on masked grids
+    pub fn supports_mask(self) -> bool {
+        self.supports_3d()
+    }
+
+    /// The algorithm and options to use where a perfect maze is required, with a single path
+    /// between any two cells: the rooms of the recursive division are shrunk to single cells, and
+    /// the cellular automaton, which leaves pockets of rock, falls back to the recursive backtracker
+    pub fn perfect(self, options: &GeneratorOptions) -> (Algorithm, GeneratorOptions) {
+        let mut options = *options;
+        match self {
+            Algorithm::CellularAutomaton => (Algorithm::RecursiveBacktracker, options),
+            Algorithm::RecursiveDivision => {
+                options.room_size = options.room_size.min(1);
+                (self, options)
+            }
+            _ => (self, options),
+        }
+    }
+
+    /// Whether the algorithm grows the maze from its start cell rather than working on the whole grid
+    fn grows_from_start(self) -> bool {
+        self.supports_3d() && self != Algorithm::Kruskal
+    }
+
+    /// The algorithm actually used on `grid`, falling back to the recursive backtracker when
+    /// this one can't handle its shape
+    fn for_grid(self, grid: &Grid) -> Algorithm {
+        let masked = grid.cells().iter().any(|cell| cell.void());
+        if (grid.depth() > 1 && !self.supports_3d())
+            || (grid.wrap() != Wrap::None && !self.supports_wrap())
+            || (masked && !self.supports_mask())
+        {
+            Algorithm::RecursiveBacktracker
+        } else {
+            self
+        }
+    }
+
+    /// Instantiates the algorithm for any `Topology`, only the recursive backtracker and Kruskal's
+    /// are available there and the other algorithms fall back to the recursive backtracker
+    pub fn build_generic<G: Topology + 'static>(
+        self,
+        grid: &mut G,
+        rng: &mut SmallRng,
+        start: G::Cell,
+    ) -> Box<dyn MazeAlgorithm<G>> {
+        if grid.is_empty() {
+            return Box::new(Finished);
+        }
+        match self {
+            Algorithm::Kruskal => Box::new(GenericKruskal::new(grid, rng)),
+            _ => Box::new(GenericBacktracker::new(grid, start)),
+        }
+    }
+
+    /// Instantiates the algorithm for a freshly created grid, starting from the options' start cell
+    pub fn build(
+        self,
+        grid: &mut Grid,
+        rng: &mut SmallRng,
+        options: &GeneratorOptions,
+    ) -> Box<dyn MazeAlgorithm> {
+        let start = Position::new_3d(options.start_x, options.start_y, options.start_z);
+        self.build_with_starts(grid, rng, options, &[start])
+    }
+
+    /// Instantiates the algorithm for a freshly created grid, growing a tree from each start and
+    /// joining them at the end. Algorithms that don't support multiple starts only use the first one,
+    /// and the ones that don't need a start ignore them. Starts on void cells are ignored, and each
+    /// part of the grid separated from the others by void cells gets a start if it has none.
+    pub fn build_with_starts(
+        self,
+        grid: &mut Grid,
+        rng: &mut SmallRng,
+        options: &GeneratorOptions,
+        starts: &[Position],
+    ) -> Box<dyn MazeAlgorithm> {
+        let algorithm = self.for_grid(grid);
+        let mut starts = starts
+            .iter()
+            .map(|pos| {
+                Position::new_3d(
+                    pos.x.min(grid.width().saturating_sub(1)),
+                    pos.y.min(grid.height().saturating_sub(1)),
+                    pos.z.min(grid.depth().saturating_sub(1)),
+                )
+            })
+            .filter(|&pos| !grid.is_void(pos))
+            .collect::<Vec<_>>();
+        if !algorithm.supports_multiple_starts() {
+            starts.truncate(1);
+        }
+        if algorithm.grows_from_start() {
+            // Each part of the grid cut off from the others by void cells needs its own start
+            let components = grid.components();
+            let count = components.iter().flatten().max().map_or(0, |&max| max + 1);
+            let mut started = vec![false; count];
+            for &start in starts.iter() {
+                if let Some(component) = components[grid.offset(start)] {
+                    started[component] = true;
+                }
+            }
+            for pos in grid.positions() {
+                if let Some(component) = components[grid.offset(pos)] {
+                    if !started[component] {
+                        started[component] = true;
+                        starts.push(pos);
+                    }
+                }
+            }
+        }
+        if starts.is_empty() {
+            if algorithm.grows_from_start() {
+                // Every cell is void
+                return Box::new(Finished);
+            }
+            starts.push(Position::new(0, 0));
+        }
+        if starts.len() > 1 && algorithm.grows_from_start() {
+            let mut generators = vec![];
+            for &start in starts.iter() {
+                if !grid.visited(start) {
+                    generators.push(algorithm.build_single(grid, rng, options, start));
+                }
+            }
+            return Box::new(MultiSeed::new(generators));
+        }
+        algorithm.build_single(grid, rng, options, starts[0])
+    }
+
+    fn build_single(
+        self,
+        grid: &mut Grid,
+        rng: &mut SmallRng,
+        options: &GeneratorOptions,
+        start: Position,
+    ) -> Box<dyn MazeAlgorithm> {
+        let weights = DirectionWeights::new(
+            options.horizontal_weight,
+            options.vertical_weight,
+            options.straightness,
+        )
+        .with_layer(options.layer_weight);
+        match self {
+            Algorithm::RecursiveBacktracker => Box::new(
+                RecursiveBacktracker::new(grid, start)
+                    .with_weights(weights)
+                    .with_weave(options.weave),
+            ),
+            Algorithm::Prim => Box::new(Prim::new(grid, start)),
+            Algorithm::Kruskal => Box::new(Kruskal::new(grid, rng)),
+            Algorithm::Wilson => Box::new(Wilson::new(grid, start, rng)),
+            Algorithm::AldousBroder => Box::new(AldousBroder::new(grid, start)),
+            Algorithm::AldousBroderWilson => Box::new(AldousBroderWilson::new(
+                grid,
+                start,
+                options.hybrid_fraction,
+            )),
+            Algorithm::GrowingTree => Box::new(
+                GrowingTree::new(
+                    grid,
+                    start,
+                    SelectionPolicy::new(
+                        options.growing_tree_newest,
+                        options.growing_tree_random,
+                        options.growing_tree_oldest,
+                    ),
+                )
+                .with_weights(weights),
+            ),
+            Algorithm::RecursiveDivision => {
+                Box::new(RecursiveDivision::new(grid, options.room_size))
+            }
+            Algorithm::BinaryTree => Box::new(BinaryTree::new(options.bias)),
+            Algorithm::Sidewinder => Box::new(Sidewinder::new(options.bias)),
+            Algorithm::HuntAndKill => Box::new(HuntAndKill::new(grid, start).with_weights(weights)),
+            Algorithm::CellularAutomaton => Box::new(CellularAutomaton::new(
+                grid,
+                rng,
+                options.automaton_rule,
+                options.automaton_fill,
+                options.automaton_generations,
+            )),
+        }
+    }
+}
+
+/// Corner towards which the passages of the biased algorithms lead
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Bias {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+impl Bias {
+    pub fn vertical(self) -> Direction {
+        match self {
+            Bias::TopLeft | Bias::TopRight => Direction::Top,
+            Bias::BottomLeft | Bias::BottomRight => Direction::Bottom,
+        }
+    }
+    pub fn horizontal(self) -> Direction {
+        match self {
+            Bias::TopLeft | Bias::BottomLeft => Direction::Left,
+            Bias::TopRight | Bias::BottomRight => Direction::Right,
+        }
+    }
+}
+
+/// Tuning parameters of the algorithms, each algorithm only reads the fields relevant to it
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug)]
+pub struct GeneratorOptions {
+    /// Fraction of the cells visited by Aldous-Broder before switching to Wilson's in the hybrid
+    pub hybrid_fraction: f64,
+    /// Weight of picking the newest cell in the growing tree
+    pub growing_tree_newest: f64,
+    /// Weight of picking a random cell in the growing tree
+    pub growing_tree_random: f64,
+    /// Weight of picking the oldest cell in the growing tree
+    pub growing_tree_oldest: f64,
+    /// Chambers at most this big in both directions are left open by the recursive division
+    pub room_size: usize,
+    /// Corner the binary tree and sidewinder algorithms carve towards
+    pub bias: Bias,
+    /// Rule of the cellular automaton
+    pub automaton_rule: AutomatonRule,
+    /// Probability of each block of the cellular automaton to start as rock
+    pub automaton_fill: f64,
+    /// Maximum number of generations of the cellular automaton, it also stops once stable
+    pub automaton_generations: usize,
+    /// Column of the cell the generation starts from
+    pub start_x: usize,
+    /// Row of the cell the generation starts from
+    pub start_y: usize,
+    /// Layer of the cell the generation starts from
+    pub start_z: usize,
+    /// Weight of carving left or right in the backtracker, growing tree and hunt-and-kill
+    pub horizontal_weight: f64,
+    /// Weight of carving up or down in the backtracker, growing tree and hunt-and-kill
+    pub vertical_weight: f64,
+    /// Preference for continuing in the same direction, negative values down to -1 prefer turning
+    pub straightness: f64,
+    /// Weight of carving to another layer in the backtracker, growing tree and hunt-and-kill
+    pub layer_weight: f64,
+    /// Which borders of the grid are connected to each other
+    pub wrap: Wrap,
+    /// Lets the recursive backtracker tunnel under corridors, creating crossings
+    pub weave: bool,
+}
+#[wasm_bindgen]
+impl GeneratorOptions {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        Self {
+            hybrid_fraction: 0.5,
+            growing_tree_newest: 1.0,
+            growing_tree_random: 0.0,
+            growing_tree_oldest: 0.0,
+            room_size: 1,
+            bias: Bias::TopLeft,
+            automaton_rule: AutomatonRule::Maze,
+            automaton_fill: 0.45,
+            automaton_generations: 100,
+            start_x: 0,
+            start_y: 0,
+            start_z: 0,
+            horizontal_weight: 1.0,
+            vertical_weight: 1.0,
+            straightness: 0.0,
+            layer_weight: 1.0,
+            wrap: Wrap::None,
+            weave: false,
+        }
+    }
+}
+impl Default for GeneratorOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,87 @@
+use super::DisjointSet;
+use crate::{Direction, Grid, MazeAlgorithm, Position};
+use rand::{rngs::SmallRng, seq::SliceRandom};
+
+/// Grows an independent tree from each start, taking turns between the generators, then joins the
+/// trees by knocking out one wall between each of them once every generator is done.
+/// The generators must only ever connect an unvisited cell to a single visited one.
+#[derive(Debug)]
+pub struct MultiSeed {
+    generators: Vec<Box<dyn MazeAlgorithm>>,
+    current: usize,
+    joins: Option<(Vec<(Position, Direction)>, DisjointSet)>,
+}
+impl MultiSeed {
+    pub fn new(generators: Vec<Box<dyn MazeAlgorithm>>) -> Self {
+        Self {
+            generators,
+            current: 0,
+            joins: None,
+        }
+    }
+
+    /// Groups the cells by tree and lists the walls separating two different trees, except the
+    /// walls of crossings which must keep their two straight passages
+    fn find_joins(grid: &Grid, rng: &mut SmallRng) -> (Vec<(Position, Direction)>, DisjointSet) {
+        let mut sets = DisjointSet::new(grid.len());
+        let mut walls = vec![];
+        for pos in grid.positions() {
+            for &dir in grid.forward_directions() {
+                if let Some(next) = grid.tunnel(pos, dir) {
+                    sets.union(grid.offset(pos), grid.offset(next));
+                }
+                if let Some(next) = grid.neighbour(pos, dir) {
+                    if !grid.has_wall(pos, dir) {
+                        sets.union(grid.offset(pos), grid.offset(next));
+                    } else if !grid.cell(pos).crossing() && !grid.cell(next).crossing() {
+                        walls.push((pos, dir));
+                    }
+                }
+            }
+        }
+        walls.retain(|&(pos, dir)| {
+            let next = grid.neighbour(pos, dir).unwrap();
+            let a = grid.offset(pos);
+            let b = grid.offset(next);
+            sets.find(a) != sets.find(b)
+        });
+        walls.shuffle(rng);
+        (walls, sets)
+    }
+}
+impl MazeAlgorithm for MultiSeed {
+    fn step(&mut self, grid: &mut Grid, rng: &mut SmallRng) -> bool {
+        if !self.generators.is_empty() {
+            if self.generators[self.current].step(grid, rng) {
+                self.generators.remove(self.current);
+            } else {
+                self.current += 1;
+            }
+            if self.current >= self.generators.len() {
+                self.current = 0;
+            }
+            return false;
+        }
+
+        let (walls, sets) = self
+            .joins
+            .get_or_insert_with(|| Self::find_joins(grid, rng));
+        while let Some((pos, dir)) = walls.pop() {
+            let next = grid.neighbour(pos, dir).unwrap();
+            let a = grid.offset(pos);
+            let b = grid.offset(next);
+            if sets.union(a, b) {
+                grid.carve(pos, dir);
+                return walls.is_empty();
+            }
+        }
+        true
+    }
+
+    fn active(&self) -> &[Position] {
+        match self.generators.get(self.current) {
+            Some(generator) => generator.active(),
+            None => &[],
+        }
+    }
+}
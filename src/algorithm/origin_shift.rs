@@ -0,0 +1,87 @@
+use crate::{Direction, Grid, Position};
+use rand::{rngs::SmallRng, seq::SliceRandom};
+use std::collections::VecDeque;
+
+/// Origin shift algorithm, keeping a perfect maze as a tree rooted at an origin cell, where every
+/// other cell points towards its parent. Each shift moves the origin to a random neighbour, which
+/// rewires a single passage while keeping the maze perfect. In weave mazes a parent can be on the
+/// other side of a crossing, and the origin never moves onto a crossing so that its passages stay
+/// straight.
+#[derive(Debug)]
+pub struct OriginShift {
+    origin: Position,
+    parents: Vec<Option<Direction>>,
+}
+impl OriginShift {
+    /// Roots the passages of an already generated maze at `origin`, which must not be a crossing
+    pub fn new(grid: &Grid, origin: Position) -> Self {
+        let mut parents = vec![None; grid.len()];
+        let mut seen = vec![false; grid.len()];
+        seen[grid.offset(origin)] = true;
+
+        let mut queue = VecDeque::new();
+        queue.push_back(origin);
+        while let Some(pos) = queue.pop_front() {
+            for &dir in grid.directions() {
+                let next = match grid.passage(pos, dir) {
+                    Some(next) => next,
+                    None => continue,
+                };
+                let offset = grid.offset(next);
+                if !seen[offset] {
+                    seen[offset] = true;
+                    parents[offset] = Some(dir.opposite());
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        Self { origin, parents }
+    }
+
+    pub fn origin(&self) -> Position {
+        self.origin
+    }
+    /// Direction of the parent of the cell, `None` for the origin
+    pub fn parent(&self, grid: &Grid, pos: Position) -> Option<Direction> {
+        self.parents[grid.offset(pos)]
+    }
+
+    /// Moves the origin to a random neighbour, skipping the moves that would carve into a crossing
+    /// or close one of its passages
+    pub fn shift(&mut self, grid: &mut Grid, rng: &mut SmallRng) {
+        let dirs = grid
+            .directions()
+            .iter()
+            .copied()
+            .filter(|&dir| match grid.neighbour(self.origin, dir) {
+                Some(next) => !grid.cell(next).crossing() && !self.closes_crossing(grid, next),
+                None => false,
+            })
+            .collect::<Vec<_>>();
+        let dir = match dirs.choose(rng) {
+            Some(&dir) => dir,
+            None => return,
+        };
+        let next = grid.neighbour(self.origin, dir).unwrap();
+        let next_offset = grid.offset(next);
+
+        if let Some(parent) = self.parents[next_offset].take() {
+            grid.cut(next, parent);
+        }
+        grid.carve(self.origin, dir);
+        let origin_offset = grid.offset(self.origin);
+        self.parents[origin_offset] = Some(dir);
+        self.origin = next;
+    }
+
+    /// Whether cutting the cell from its parent would close a wall of a crossing
+    fn closes_crossing(&self, grid: &Grid, pos: Position) -> bool {
+        match self.parents[grid.offset(pos)] {
+            Some(parent) if !grid.has_wall(pos, parent) => grid
+                .neighbour(pos, parent)
+                .is_some_and(|parent| grid.cell(parent).crossing()),
+            _ => false,
+        }
+    }
+}
@@ -0,0 +1,61 @@
+use crate::{Grid, MazeAlgorithm, Position};
+use rand::{rngs::SmallRng, seq::SliceRandom, Rng};
+
+/// Randomized Prim's algorithm, growing the maze from a frontier of cells around the visited area
+#[derive(Debug)]
+pub struct Prim {
+    frontier: Vec<Position>,
+    in_frontier: Vec<bool>,
+}
+impl Prim {
+    pub fn new(grid: &mut Grid, start: Position) -> Self {
+        let mut this = Self {
+            frontier: vec![],
+            in_frontier: vec![false; grid.len()],
+        };
+        this.visit(grid, start);
+        this
+    }
+
+    fn visit(&mut self, grid: &mut Grid, pos: Position) {
+        grid.set_visited(pos, true);
+        for &dir in grid.directions() {
+            if let Some(next) = grid.neighbour(pos, dir) {
+                let offset = grid.offset(next);
+                if !grid.visited(next) && !self.in_frontier[offset] {
+                    self.in_frontier[offset] = true;
+                    self.frontier.push(next);
+                }
+            }
+        }
+    }
+}
+impl MazeAlgorithm for Prim {
+    fn step(&mut self, grid: &mut Grid, rng: &mut SmallRng) -> bool {
+        if self.frontier.is_empty() {
+            return true;
+        }
+        let pos = self
+            .frontier
+            .swap_remove(rng.gen_range(0, self.frontier.len()));
+        // Another tree may have reached the cell first when growing from several starts
+        if grid.visited(pos) {
+            return self.frontier.is_empty();
+        }
+
+        let mut dirs = grid.directions().to_vec();
+        dirs.shuffle(rng);
+        let dir = dirs
+            .iter()
+            .copied()
+            .find(|&dir| {
+                grid.neighbour(pos, dir)
+                    .is_some_and(|next| grid.visited(next))
+            })
+            .expect("frontier cells always touch the visited area");
+        grid.carve(pos, dir);
+        self.visit(grid, pos);
+
+        false
+    }
+}
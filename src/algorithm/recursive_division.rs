@@ -0,0 +1,104 @@
+use crate::{Direction, Grid, MazeAlgorithm, Position};
+use rand::{rngs::SmallRng, Rng};
+
+#[derive(Clone, Copy, Debug)]
+struct Chamber {
+    x: usize,
+    y: usize,
+    width: usize,
+    height: usize,
+}
+
+/// Recursive division, starting from an open grid and splitting it into chambers with walls
+/// that have a single gap. Chambers no larger than `room_size` in both directions are left open as rooms.
+#[derive(Debug)]
+pub struct RecursiveDivision {
+    room_size: usize,
+    chambers: Vec<Chamber>,
+}
+impl RecursiveDivision {
+    pub fn new(grid: &mut Grid, room_size: usize) -> Self {
+        for y in 0..grid.height() {
+            for x in 0..grid.width() {
+                let pos = Position::new(x, y);
+                grid.set_visited(pos, true);
+                for &dir in [Direction::Right, Direction::Bottom].iter() {
+                    if grid.neighbour(pos, dir).is_some() {
+                        grid.carve(pos, dir);
+                    }
+                }
+            }
+        }
+        Self {
+            room_size,
+            chambers: vec![Chamber {
+                x: 0,
+                y: 0,
+                width: grid.width(),
+                height: grid.height(),
+            }],
+        }
+    }
+
+    fn is_final(&self, chamber: &Chamber) -> bool {
+        chamber.width < 2
+            || chamber.height < 2
+            || (chamber.width <= self.room_size && chamber.height <= self.room_size)
+    }
+}
+impl MazeAlgorithm for RecursiveDivision {
+    fn step(&mut self, grid: &mut Grid, rng: &mut SmallRng) -> bool {
+        let chamber = loop {
+            match self.chambers.pop() {
+                None => return true,
+                Some(chamber) if self.is_final(&chamber) => continue,
+                Some(chamber) => break chamber,
+            }
+        };
+        let Chamber {
+            x,
+            y,
+            width,
+            height,
+        } = chamber;
+
+        let horizontal = match width.cmp(&height) {
+            std::cmp::Ordering::Less => true,
+            std::cmp::Ordering::Greater => false,
+            std::cmp::Ordering::Equal => rng.gen(),
+        };
+        if horizontal {
+            let split = rng.gen_range(1, height);
+            let gap = rng.gen_range(0, width);
+            for i in (0..width).filter(|&i| i != gap) {
+                grid.set_wall(Position::new(x + i, y + split - 1), Direction::Bottom, true);
+            }
+            self.chambers.push(Chamber {
+                height: split,
+                ..chamber
+            });
+            self.chambers.push(Chamber {
+                y: y + split,
+                height: height - split,
+                ..chamber
+            });
+        } else {
+            let split = rng.gen_range(1, width);
+            let gap = rng.gen_range(0, height);
+            for i in (0..height).filter(|&i| i != gap) {
+                grid.set_wall(Position::new(x + split - 1, y + i), Direction::Right, true);
+            }
+            self.chambers.push(Chamber {
+                width: split,
+                ..chamber
+            });
+            self.chambers.push(Chamber {
+                x: x + split,
+                width: width - split,
+                ..chamber
+            });
+        }
+
+        self.chambers.iter().all(|chamber| self.is_final(chamber))
+    }
+}
@@ -0,0 +1,62 @@
+use super::Bias;
+use crate::{Direction, Grid, MazeAlgorithm, Position};
+use rand::{rngs::SmallRng, Rng};
+
+/// Sidewinder algorithm, carving runs of cells along each row and closing every run with a
+/// passage towards the vertical side of the bias corner, which is left as a single corridor.
+/// Only remembers the current cell and the start of the current run.
+#[derive(Debug)]
+pub struct Sidewinder {
+    bias: Bias,
+    index: usize,
+    run_start: usize,
+}
+impl Sidewinder {
+    pub fn new(bias: Bias) -> Self {
+        Self {
+            bias,
+            index: 0,
+            run_start: 0,
+        }
+    }
+
+    /// Position of the `i`th cell of row `y`, in the order the runs are carved
+    fn run_position(&self, grid: &Grid, i: usize, y: usize) -> Position {
+        match self.bias.horizontal() {
+            Direction::Left => Position::new(grid.width() - 1 - i, y),
+            _ => Position::new(i, y),
+        }
+    }
+}
+impl MazeAlgorithm for Sidewinder {
+    fn step(&mut self, grid: &mut Grid, rng: &mut SmallRng) -> bool {
+        if self.index >= grid.len() {
+            return true;
+        }
+        let i = self.index % grid.width();
+        let y = self.index / grid.width();
+        let pos = self.run_position(grid, i, y);
+        self.index += 1;
+
+        let vertical = self.bias.vertical();
+        let horizontal = self.bias.horizontal();
+        let can_continue = grid.neighbour(pos, horizontal).is_some();
+        if grid.neighbour(pos, vertical).is_none() {
+            if can_continue {
+                grid.carve(pos, horizontal);
+            }
+        } else if !can_continue || rng.gen() {
+            let exit = rng.gen_range(self.run_start, i + 1);
+            grid.carve(self.run_position(grid, exit, y), vertical);
+            self.run_start = i + 1;
+        } else {
+            grid.carve(pos, horizontal);
+        }
+        if !can_continue {
+            self.run_start = 0;
+        }
+        grid.set_visited(pos, true);
+
+        self.index >= grid.len()
+    }
+}
@@ -0,0 +1,103 @@
+use crate::{Direction, Grid, MazeAlgorithm, Position};
+use rand::{rngs::SmallRng, seq::SliceRandom};
+
+/// Wilson's algorithm, adding loop-erased random walks to the maze until every cell is reached.
+/// Samples uniformly from all the spanning trees of the grid.
+#[derive(Debug)]
+pub struct Wilson {
+    remaining: Vec<Position>,
+    walk: Vec<Position>,
+    walk_dirs: Vec<Direction>,
+    walk_index: Vec<Option<usize>>,
+}
+impl Wilson {
+    pub fn new(grid: &mut Grid, start: Position, rng: &mut SmallRng) -> Self {
+        grid.set_visited(start, true);
+        Self::from_component(grid, start, rng)
+    }
+
+    /// Continues growing the tree containing `start`, only within the part of the grid it can reach
+    pub fn from_component(grid: &Grid, start: Position, rng: &mut SmallRng) -> Self {
+        let component = grid.component(start);
+        let remaining = grid
+            .positions()
+            .filter(|&pos| component[grid.offset(pos)] && !grid.visited(pos))
+            .collect();
+        Self::with_remaining(grid, remaining, rng)
+    }
+
+    fn with_remaining(grid: &Grid, mut remaining: Vec<Position>, rng: &mut SmallRng) -> Self {
+        remaining.shuffle(rng);
+        Self {
+            remaining,
+            walk: vec![],
+            walk_dirs: vec![],
+            walk_index: vec![None; grid.len()],
+        }
+    }
+
+    fn push_walk(&mut self, grid: &Grid, pos: Position) {
+        self.walk_index[grid.offset(pos)] = Some(self.walk.len());
+        self.walk.push(pos);
+    }
+
+    /// Erases the loop formed by walking back onto the walk at `index`
+    fn erase_loop(&mut self, grid: &Grid, index: usize) {
+        for pos in self.walk.drain(index + 1..) {
+            self.walk_index[grid.offset(pos)] = None;
+        }
+        self.walk_dirs.truncate(index);
+    }
+
+    /// Carves the whole walk into the maze
+    fn commit_walk(&mut self, grid: &mut Grid) {
+        for (&pos, &dir) in self.walk.iter().zip(self.walk_dirs.iter()) {
+            grid.carve(pos, dir);
+        }
+        for pos in self.walk.drain(..) {
+            grid.set_visited(pos, true);
+            self.walk_index[grid.offset(pos)] = None;
+        }
+        self.walk_dirs.clear();
+    }
+}
+impl MazeAlgorithm for Wilson {
+    fn step(&mut self, grid: &mut Grid, rng: &mut SmallRng) -> bool {
+        let cursor = match self.walk.last() {
+            Some(&cursor) => cursor,
+            None => {
+                while let Some(pos) = self.remaining.pop() {
+                    if !grid.visited(pos) {
+                        self.push_walk(grid, pos);
+                        return false;
+                    }
+                }
+                return true;
+            }
+        };
+
+        let dirs = grid
+            .directions()
+            .iter()
+            .copied()
+            .filter(|&dir| grid.neighbour(cursor, dir).is_some())
+            .collect::<Vec<_>>();
+        let dir = *dirs.choose(rng).unwrap();
+        let next = grid.neighbour(cursor, dir).unwrap();
+        self.walk_dirs.push(dir);
+
+        if grid.visited(next) {
+            self.commit_walk(grid);
+        } else if let Some(index) = self.walk_index[grid.offset(next)] {
+            self.erase_loop(grid, index);
+        } else {
+            self.push_walk(grid, next);
+        }
+
+        false
+    }
+
+    fn active(&self) -> &[Position] {
+        &self.walk
+    }
+}
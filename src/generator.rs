@@ -0,0 +1,73 @@
+use crate::{MazeAlgorithm, Topology};
+use rand::rngs::SmallRng;
+use std::fmt;
+
+/// Drives an algorithm over a grid step by step, shared by the maze types of every shape
+pub struct Generator<G: Topology> {
+    grid: G,
+    rng: SmallRng,
+    algorithm: Box<dyn MazeAlgorithm<G>>,
+    finished: bool,
+}
+impl<G: Topology> Generator<G> {
+    /// The algorithm must already be initialized for `grid`
+    pub fn new(grid: G, rng: SmallRng, algorithm: Box<dyn MazeAlgorithm<G>>) -> Self {
+        Self {
+            grid,
+            rng,
+            algorithm,
+            finished: false,
+        }
+    }
+
+    pub fn grid(&self) -> &G {
+        &self.grid
+    }
+    /// Mutable access to the grid and the rng, to post-process the maze
+    pub fn parts_mut(&mut self) -> (&mut G, &mut SmallRng) {
+        (&mut self.grid, &mut self.rng)
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+    pub fn active(&self) -> &[G::Cell] {
+        self.algorithm.active()
+    }
+
+    pub fn gen_step(&mut self) -> bool {
+        if !self.finished {
+            self.finished = self.algorithm.step(&mut self.grid, &mut self.rng);
+        }
+        self.finished
+    }
+    pub fn generate(&mut self, limit: Option<usize>) -> bool {
+        match limit {
+            None => {
+                if !self.finished {
+                    self.algorithm.finish(&mut self.grid, &mut self.rng);
+                    self.finished = true;
+                }
+                true
+            }
+            Some(limit) => {
+                for _ in 0..limit {
+                    if self.gen_step() {
+                        return true;
+                    }
+                }
+                false
+            }
+        }
+    }
+}
+impl<G: Topology + fmt::Debug> fmt::Debug for Generator<G> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Generator")
+            .field("grid", &self.grid)
+            .field("rng", &self.rng)
+            .field("algorithm", &self.algorithm)
+            .field("finished", &self.finished)
+            .finish()
+    }
+}
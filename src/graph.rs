@@ -0,0 +1,267 @@
+use crate::{Algorithm, Generator, MazeAlgorithm, Topology};
+use rand::{rngs::SmallRng, SeedableRng};
+use wasm_bindgen::prelude::*;
+
+/// A maze over any graph, given as a number of nodes and the edges a passage can be carved along.
+/// Generating it picks a spanning tree of the graph, or a spanning forest with Kruskal's algorithm
+/// when the graph isn't connected; the other algorithms only reach the nodes connected to node 0.
+#[wasm_bindgen]
+#[derive(Debug)]
+pub struct GraphMaze {
+    generator: Generator<Graph>,
+}
+#[wasm_bindgen]
+impl GraphMaze {
+    /// `edges` is a flat list of pairs of node indices
+    pub fn new(nodes: usize, edges: &[usize], algorithm: Algorithm) -> Self {
+        Self::with_rng(
+            Graph::from_pairs(nodes, edges),
+            SmallRng::from_entropy(),
+            algorithm,
+        )
+    }
+    /// `edges` is a flat list of pairs of node indices
+    pub fn from_seed(nodes: usize, edges: &[usize], seed: u64, algorithm: Algorithm) -> Self {
+        Self::with_rng(
+            Graph::from_pairs(nodes, edges),
+            SmallRng::seed_from_u64(seed),
+            algorithm,
+        )
+    }
+
+    pub fn nodes(&self) -> usize {
+        self.graph().len()
+    }
+    pub fn edges_len(&self) -> usize {
+        self.graph().edges().len()
+    }
+    /// Whether a passage was carved along the edge, edges being numbered in the order they were
+    /// given, including the ignored ones which are never open
+    pub fn is_open(&self, edge: usize) -> bool {
+        self.graph().is_open(edge)
+    }
+    /// The carved edges as a flat list of pairs of node indices, in the order they were carved
+    pub fn tree(&self) -> Vec<usize> {
+        self.graph().tree().flat_map(|(a, b)| vec![a, b]).collect()
+    }
+
+    pub fn active_nodes_ptr(&self) -> *const usize {
+        self.generator.active().as_ptr()
+    }
+    pub fn active_nodes_len(&self) -> usize {
+        self.generator.active().len()
+    }
+
+    pub fn gen_step(&mut self) -> bool {
+        self.generator.gen_step()
+    }
+    pub fn generate(&mut self, limit: Option<usize>) -> bool {
+        self.generator.generate(limit)
+    }
+}
+impl GraphMaze {
+    fn with_rng(mut graph: Graph, mut rng: SmallRng, algorithm: Algorithm) -> Self {
+        let algorithm = algorithm.build_generic(&mut graph, &mut rng, 0);
+        Self {
+            generator: Generator::new(graph, rng, algorithm),
+        }
+    }
+
+    pub fn graph(&self) -> &Graph {
+        self.generator.grid()
+    }
+}
+
+/// Nodes linked by edges which start closed and are opened by the algorithms.
+/// Every other `Topology` can be turned into a graph with `from_topology`, and the tree picked on
+/// the graph carved back into it with `apply`.
+#[derive(Clone, Debug)]
+pub struct Graph {
+    edges: Vec<(usize, usize)>,
+    open: Vec<bool>,
+    /// Indices of the open edges, in the order they were opened
+    opened: Vec<usize>,
+    /// Neighbouring node and edge index, for each node
+    adjacency: Vec<Vec<(usize, usize)>>,
+    visited: Vec<bool>,
+}
+impl Graph {
+    /// Edges joining a node to itself or to a node that doesn't exist are kept, so that the edges
+    /// stay numbered in the order they were given, but are never opened
+    pub fn new(nodes: usize, edges: &[(usize, usize)]) -> Self {
+        let mut adjacency = vec![vec![]; nodes];
+        for (edge, &(a, b)) in edges.iter().enumerate() {
+            if a != b && a < nodes && b < nodes {
+                adjacency[a].push((b, edge));
+                adjacency[b].push((a, edge));
+            }
+        }
+        Self {
+            edges: edges.to_vec(),
+            open: vec![false; edges.len()],
+            opened: vec![],
+            adjacency,
+            visited: vec![false; nodes],
+        }
+    }
+    fn from_pairs(nodes: usize, pairs: &[usize]) -> Self {
+        let edges = pairs
+            .chunks_exact(2)
+            .map(|pair| (pair[0], pair[1]))
+            .collect::<Vec<_>>();
+        Self::new(nodes, &edges)
+    }
+    /// The graph of the cells of a topology, nodes being the indices of the cells
+    pub fn from_topology<T: Topology>(topology: &T) -> Self {
+        let mut edges = vec![];
+        for index in 0..topology.len() {
+            for next in topology.neighbours(topology.cell(index)) {
+                if topology.index(next) > index {
+                    edges.push((index, topology.index(next)));
+                }
+            }
+        }
+        Self::new(topology.len(), &edges)
+    }
+
+    pub fn edges(&self) -> &[(usize, usize)] {
+        &self.edges
+    }
+    pub fn is_open(&self, edge: usize) -> bool {
+        self.open[edge]
+    }
+    /// The open edges, in the order they were opened
+    pub fn tree(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.opened.iter().map(move |&edge| self.edges[edge])
+    }
+
+    /// Carves the open edges into the topology this graph was made from
+    pub fn apply<T: Topology>(&self, topology: &mut T) {
+        self.apply_from(topology, 0);
+    }
+    /// Carves the open edges into the topology, skipping the `first` ones opened
+    fn apply_from<T: Topology>(&self, topology: &mut T, first: usize) {
+        for (a, b) in self.tree().skip(first) {
+            let (a, b) = (topology.cell(a), topology.cell(b));
+            topology.link(a, b);
+            topology.set_visited(a, true);
+            topology.set_visited(b, true);
+        }
+    }
+}
+impl Topology for Graph {
+    type Cell = usize;
+
+    fn len(&self) -> usize {
+        self.adjacency.len()
+    }
+    fn index(&self, cell: usize) -> usize {
+        cell
+    }
+    fn cell(&self, index: usize) -> usize {
+        index
+    }
+    fn neighbours(&self, cell: usize) -> Vec<usize> {
+        self.adjacency[cell].iter().map(|&(next, _)| next).collect()
+    }
+    fn link(&mut self, a: usize, b: usize) {
+        if let Some(&(_, edge)) = self.adjacency[a]
+            .iter()
+            .find(|&&(next, edge)| next == b && !self.open[edge])
+        {
+            self.open[edge] = true;
+            self.opened.push(edge);
+        }
+    }
+
+    fn visited(&self, cell: usize) -> bool {
+        self.visited[cell]
+    }
+    fn set_visited(&mut self, cell: usize, visited: bool) {
+        self.visited[cell] = visited;
+    }
+}
+
+/// Runs an algorithm on the graph of the cells of a topology and carves every edge it opens back
+/// into the topology, which makes any `Topology`, like the rectangular grid of a `Maze`, a special
+/// case of a `Graph`
+#[derive(Debug)]
+pub struct GraphAlgorithm<C> {
+    graph: Graph,
+    algorithm: Box<dyn MazeAlgorithm<Graph>>,
+    /// Number of opened edges already carved into the topology
+    applied: usize,
+    active: Vec<C>,
+}
+impl<C: Copy> GraphAlgorithm<C> {
+    pub fn new<T: Topology<Cell = C>>(
+        topology: &mut T,
+        rng: &mut SmallRng,
+        algorithm: Algorithm,
+        start: C,
+    ) -> Self {
+        let mut graph = Graph::from_topology(topology);
+        let algorithm = algorithm.build_generic(&mut graph, rng, topology.index(start));
+        for node in 0..graph.len() {
+            if graph.visited(node) {
+                topology.set_visited(topology.cell(node), true);
+            }
+        }
+        let mut this = Self {
+            graph,
+            algorithm,
+            applied: 0,
+            active: vec![],
+        };
+        this.sync(topology);
+        this
+    }
+
+    /// Carves the edges opened since the last call and maps the active nodes to their cells
+    fn sync<T: Topology<Cell = C>>(&mut self, topology: &mut T) {
+        self.graph.apply_from(topology, self.applied);
+        self.applied = self.graph.opened.len();
+        self.active = self
+            .algorithm
+            .active()
+            .iter()
+            .map(|&node| topology.cell(node))
+            .collect();
+    }
+}
+impl<T: Topology> MazeAlgorithm<T> for GraphAlgorithm<T::Cell> {
+    fn step(&mut self, topology: &mut T, rng: &mut SmallRng) -> bool {
+        let finished = self.algorithm.step(&mut self.graph, rng);
+        self.sync(topology);
+        finished
+    }
+
+    fn active(&self) -> &[T::Cell] {
+        &self.active
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ignored_edges_keep_their_number() {
+        let mut maze = GraphMaze::from_seed(3, &[0, 0, 0, 1, 1, 2, 2, 7], 1, Algorithm::Kruskal);
+        maze.generate(None);
+        assert_eq!(maze.edges_len(), 4);
+        assert!(!maze.is_open(0));
+        assert!(maze.is_open(1));
+        assert!(maze.is_open(2));
+        assert!(!maze.is_open(3));
+    }
+
+    #[test]
+    fn empty_graph_is_finished() {
+        for &algorithm in [Algorithm::RecursiveBacktracker, Algorithm::Kruskal].iter() {
+            let mut maze = GraphMaze::from_seed(0, &[], 1, algorithm);
+            assert!(maze.gen_step());
+            assert!(maze.tree().is_empty());
+        }
+    }
+}
@@ -0,0 +1,479 @@
+use crate::Mask;
+use bitfield::*;
+use std::collections::VecDeque;
+use wasm_bindgen::prelude::*;
+
+/// A grid of square cells, made of `depth` layers of `width * height` cells stacked on each other
+#[derive(Clone, Debug)]
+pub struct Grid {
+    width: usize,
+    height: usize,
+    depth: usize,
+    wrap: Wrap,
+
+    cells: Vec<MazeCell>,
+}
+impl Grid {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self::new_3d(width, height, 1)
+    }
+    pub fn new_3d(width: usize, height: usize, depth: usize) -> Self {
+        let mut default_cell = MazeCell::new();
+        default_cell.set_bottom(true);
+        default_cell.set_right(true);
+        default_cell.set_down(true);
+        Self {
+            width,
+            height,
+            depth,
+            wrap: Wrap::None,
+
+            cells: vec![default_cell; width * height * depth],
+        }
+    }
+
+    /// Connects the opposite borders of each layer as described by `wrap`
+    pub fn with_wrap(mut self, wrap: Wrap) -> Self {
+        self.wrap = wrap;
+        self
+    }
+
+    /// Turns the cells disabled in the mask of the same size into void cells
+    pub fn with_mask(mut self, mask: &Mask) -> Self {
+        for pos in self.positions().collect::<Vec<_>>() {
+            if !mask.enabled(pos.x, pos.y) {
+                self.cell_mut(pos).set_void(true);
+            }
+        }
+        self
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+    pub fn height(&self) -> usize {
+        self.height
+    }
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+    pub fn wrap(&self) -> Wrap {
+        self.wrap
+    }
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn cells(&self) -> &[MazeCell] {
+        &self.cells
+    }
+
+    /// Directions cells can have neighbours in, `Up` and `Down` only count with several layers
+    pub fn directions(&self) -> &'static [Direction] {
+        if self.depth > 1 {
+            &Direction::ALL
+        } else {
+            &Direction::PLANAR
+        }
+    }
+    /// Directions whose walls are stored in the cell itself
+    pub fn forward_directions(&self) -> &'static [Direction] {
+        if self.depth > 1 {
+            &[Direction::Right, Direction::Bottom, Direction::Down]
+        } else {
+            &[Direction::Right, Direction::Bottom]
+        }
+    }
+
+    /// Every position of the grid, in the order of the cells
+    pub fn positions(&self) -> impl Iterator<Item = Position> {
+        let (width, height) = (self.width, self.height);
+        (0..self.len()).map(move |offset| {
+            Position::new_3d(
+                offset % width,
+                offset / width % height,
+                offset / (width * height),
+            )
+        })
+    }
+
+    pub fn offset(&self, pos: Position) -> usize {
+        pos.x + (pos.y + pos.z * self.height) * self.width
+    }
+    pub fn cell(&self, pos: Position) -> MazeCell {
+        self.cells[self.offset(pos)]
+    }
+    pub fn cell_mut(&mut self, pos: Position) -> &mut MazeCell {
+        let offset = self.offset(pos);
+        &mut self.cells[offset]
+    }
+
+    /// Offset of a cell in the first layer
+    pub fn get_cell_offset(&self, x: usize, y: usize) -> usize {
+        self.offset(Position::new(x, y))
+    }
+    pub fn get_cell(&self, x: usize, y: usize) -> MazeCell {
+        self.cell(Position::new(x, y))
+    }
+    pub fn get_cell_mut(&mut self, x: usize, y: usize) -> &mut MazeCell {
+        self.cell_mut(Position::new(x, y))
+    }
+
+    pub fn visited(&self, pos: Position) -> bool {
+        self.cell(pos).visited()
+    }
+    pub fn set_visited(&mut self, pos: Position, visited: bool) {
+        self.cell_mut(pos).set_visited(visited);
+    }
+    /// Whether the cell is masked out of the maze
+    pub fn is_void(&self, pos: Position) -> bool {
+        self.cell(pos).void()
+    }
+
+    /// Labels each cell with the part of the grid it belongs to, parts being separated by void
+    /// cells and numbered in the order of their first cell. Void cells have no label.
+    pub fn components(&self) -> Vec<Option<usize>> {
+        let mut labels = vec![None; self.len()];
+        let mut count = 0;
+        let mut queue = VecDeque::new();
+        for start in self.positions() {
+            if self.is_void(start) || labels[self.offset(start)].is_some() {
+                continue;
+            }
+            labels[self.offset(start)] = Some(count);
+            queue.push_back(start);
+            while let Some(pos) = queue.pop_front() {
+                for &dir in self.directions() {
+                    if let Some(next) = self.neighbour(pos, dir) {
+                        let offset = self.offset(next);
+                        if labels[offset].is_none() {
+                            labels[offset] = Some(count);
+                            queue.push_back(next);
+                        }
+                    }
+                }
+            }
+            count += 1;
+        }
+        labels
+    }
+    /// Marks the cells that can be reached from `start` without going through void cells,
+    /// whatever the walls between them
+    pub fn component(&self, start: Position) -> Vec<bool> {
+        let mut reached = vec![false; self.len()];
+        if self.is_void(start) {
+            return reached;
+        }
+        reached[self.offset(start)] = true;
+        let mut queue = VecDeque::new();
+        queue.push_back(start);
+        while let Some(pos) = queue.pop_front() {
+            for &dir in self.directions() {
+                if let Some(next) = self.neighbour(pos, dir) {
+                    let offset = self.offset(next);
+                    if !reached[offset] {
+                        reached[offset] = true;
+                        queue.push_back(next);
+                    }
+                }
+            }
+        }
+        reached
+    }
+
+    /// Returns the position next to `pos` in the given direction, if it is inside the grid
+    /// or across a wrapping border. A cell is never its own neighbour, and void cells have
+    /// no neighbours.
+    pub fn neighbour(&self, pos: Position, dir: Direction) -> Option<Position> {
+        if self.is_void(pos) {
+            return None;
+        }
+        let mut next = pos;
+        match dir {
+            Direction::Left | Direction::Right if self.crosses_seam(pos, dir) => {
+                if !self.wrap.horizontal() {
+                    return None;
+                }
+                next.x = self.width - 1 - pos.x;
+                if self.wrap.twisted() {
+                    next.y = self.height - 1 - pos.y;
+                }
+            }
+            Direction::Top | Direction::Bottom if self.crosses_seam(pos, dir) => {
+                if !self.wrap.vertical() {
+                    return None;
+                }
+                next.y = self.height - 1 - pos.y;
+            }
+            Direction::Up if pos.z == 0 => return None,
+            Direction::Down if pos.z + 1 >= self.depth => return None,
+            _ => dir.apply(&mut next),
+        }
+        if next == pos || self.is_void(next) {
+            None
+        } else {
+            Some(next)
+        }
+    }
+
+    /// Whether going from `pos` in the given direction leaves the rectangle of the layer,
+    /// whether or not the border wraps there
+    pub fn crosses_seam(&self, pos: Position, dir: Direction) -> bool {
+        match dir {
+            Direction::Top => pos.y == 0,
+            Direction::Left => pos.x == 0,
+            Direction::Bottom => pos.y + 1 >= self.height,
+            Direction::Right => pos.x + 1 >= self.width,
+            Direction::Up | Direction::Down => false,
+        }
+    }
+
+    /// Whether there is a wall between `pos` and its neighbour in the given direction,
+    /// the borders of the grid that don't wrap always count as walls
+    pub fn has_wall(&self, pos: Position, dir: Direction) -> bool {
+        let next = match self.neighbour(pos, dir) {
+            Some(next) => next,
+            None => return true,
+        };
+        match dir {
+            Direction::Bottom => self.cell(pos).bottom(),
+            Direction::Right => self.cell(pos).right(),
+            Direction::Down => self.cell(pos).down(),
+            Direction::Top => self.cell(next).bottom(),
+            Direction::Left => self.cell(next).right(),
+            Direction::Up => self.cell(next).down(),
+        }
+    }
+
+    /// Returns the cell reached by tunneling under the neighbour in the given direction, if that
+    /// neighbour is a crossing whose under-passage runs along the direction
+    pub fn tunnel(&self, pos: Position, dir: Direction) -> Option<Position> {
+        let under = self.neighbour(pos, dir)?;
+        if self.cell(under).crossing() && self.is_straight_across(under, dir) {
+            self.neighbour(under, dir)
+        } else {
+            None
+        }
+    }
+    /// Whether the cell is a straight corridor perpendicular to the given planar direction, the
+    /// only shape a path can pass under
+    pub fn is_straight_across(&self, pos: Position, dir: Direction) -> bool {
+        let sides = match dir {
+            Direction::Left | Direction::Right => [Direction::Top, Direction::Bottom],
+            Direction::Top | Direction::Bottom => [Direction::Left, Direction::Right],
+            Direction::Up | Direction::Down => return false,
+        };
+        sides.iter().all(|&side| !self.has_wall(pos, side))
+            && self.has_wall(pos, dir)
+            && self.has_wall(pos, dir.opposite())
+    }
+    /// Returns the cell reached by leaving `pos` through its side in the given direction,
+    /// either through an opening or by passing under a crossing
+    pub fn passage(&self, pos: Position, dir: Direction) -> Option<Position> {
+        if self.has_wall(pos, dir) {
+            self.tunnel(pos, dir)
+        } else {
+            self.neighbour(pos, dir)
+        }
+    }
+
+    /// Closes the passage leaving `pos` through its side in the given direction, either by adding
+    /// the wall or by filling the tunnel under a crossing
+    pub fn cut(&mut self, pos: Position, dir: Direction) {
+        if self.has_wall(pos, dir) {
+            if let Some(under) = self.neighbour(pos, dir) {
+                self.cell_mut(under).set_crossing(false);
+            }
+        } else {
+            self.set_wall(pos, dir, true);
+        }
+    }
+    /// Removes the wall between `pos` and its neighbour in the given direction
+    pub fn carve(&mut self, pos: Position, dir: Direction) {
+        self.set_wall(pos, dir, false);
+    }
+    /// Adds or removes the wall between `pos` and its neighbour in the given direction.
+    /// Walls are stored in the cell on their left, top or upper side, the walls across a
+    /// wrapping border being the right and bottom walls of the last column and row.
+    pub fn set_wall(&mut self, pos: Position, dir: Direction, wall: bool) {
+        let next = match self.neighbour(pos, dir) {
+            Some(next) => next,
+            None => return,
+        };
+        match dir {
+            Direction::Bottom => self.cell_mut(pos).set_bottom(wall),
+            Direction::Right => self.cell_mut(pos).set_right(wall),
+            Direction::Down => self.cell_mut(pos).set_down(wall),
+            Direction::Top => self.cell_mut(next).set_bottom(wall),
+            Direction::Left => self.cell_mut(next).set_right(wall),
+            Direction::Up => self.cell_mut(next).set_down(wall),
+        }
+    }
+}
+
+/// How the borders of each layer of a grid are connected to each other
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Wrap {
+    /// Every border is a wall
+    None,
+    /// The left and right borders are connected
+    Cylinder,
+    /// Both the left and right borders and the top and bottom ones are connected
+    Torus,
+    /// The left and right borders are connected upside down
+    Mobius,
+    /// The left and right borders are connected upside down, the top and bottom ones are connected
+    Klein,
+}
+impl Wrap {
+    pub fn horizontal(self) -> bool {
+        self != Wrap::None
+    }
+    pub fn vertical(self) -> bool {
+        matches!(self, Wrap::Torus | Wrap::Klein)
+    }
+    /// Whether rows are flipped when crossing the left and right borders
+    pub fn twisted(self) -> bool {
+        matches!(self, Wrap::Mobius | Wrap::Klein)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Direction {
+    Top,
+    Right,
+    Left,
+    Bottom,
+    /// Towards the previous layer
+    Up,
+    /// Towards the next layer
+    Down,
+}
+impl Direction {
+    /// The directions within a layer
+    pub const PLANAR: [Direction; 4] = [
+        Direction::Left,
+        Direction::Right,
+        Direction::Top,
+        Direction::Bottom,
+    ];
+    pub const ALL: [Direction; 6] = [
+        Direction::Left,
+        Direction::Right,
+        Direction::Top,
+        Direction::Bottom,
+        Direction::Up,
+        Direction::Down,
+    ];
+
+    pub fn opposite(self) -> Direction {
+        match self {
+            Direction::Top => Direction::Bottom,
+            Direction::Right => Direction::Left,
+            Direction::Left => Direction::Right,
+            Direction::Bottom => Direction::Top,
+            Direction::Up => Direction::Down,
+            Direction::Down => Direction::Up,
+        }
+    }
+
+    pub fn apply(&self, pos: &mut Position) {
+        match self {
+            Direction::Top => {
+                pos.y -= 1;
+            }
+            Direction::Right => {
+                pos.x += 1;
+            }
+            Direction::Left => {
+                pos.x -= 1;
+            }
+            Direction::Bottom => {
+                pos.y += 1;
+            }
+            Direction::Up => {
+                pos.z -= 1;
+            }
+            Direction::Down => {
+                pos.z += 1;
+            }
+        }
+    }
+}
+
+#[wasm_bindgen]
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Position {
+    pub x: usize,
+    pub y: usize,
+    /// Layer of the position, always 0 in flat mazes
+    pub z: usize,
+}
+impl Position {
+    pub fn new(x: usize, y: usize) -> Self {
+        Self::new_3d(x, y, 0)
+    }
+    pub fn new_3d(x: usize, y: usize, z: usize) -> Self {
+        Self { x, y, z }
+    }
+}
+
+bitfield! {
+    #[wasm_bindgen]
+    #[derive(Clone, Copy, Debug)]
+    pub struct MazeCell(u8);
+    bool;
+    // The fields default to u16
+    pub visited, set_visited: 0;
+    pub right, set_right: 1;
+    pub bottom, set_bottom: 2;
+    // Wall with the cell of the next layer
+    pub down, set_down: 3;
+    // Masked out cell, never part of the maze
+    pub void, set_void: 4;
+    // The passage through the open walls goes over a tunnel running through the closed ones
+    pub crossing, set_crossing: 5;
+}
+impl MazeCell {
+    pub fn new() -> Self {
+        MazeCell(0)
+    }
+}
+impl Default for MazeCell {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Algorithm, GeneratorOptions, Maze};
+
+    #[test]
+    fn weave_3d_is_perfect() {
+        let options = GeneratorOptions {
+            weave: true,
+            horizontal_weight: 3.0,
+            straightness: 0.5,
+            ..GeneratorOptions::new()
+        };
+        for seed in 0..50 {
+            let mut maze =
+                Maze::from_seed_3d(4, 3, 3, seed, Algorithm::RecursiveBacktracker, &options);
+            maze.generate(None);
+            let grid = maze.grid();
+            let mut passages = 0;
+            for pos in grid.positions() {
+                for &dir in grid.directions() {
+                    passages += grid.passage(pos, dir).is_some() as usize;
+                }
+            }
+            assert_eq!(passages / 2, grid.len() - 1, "seed {}", seed);
+        }
+    }
+}
@@ -0,0 +1,296 @@
+use crate::{Algorithm, Generator, Topology};
+use bitfield::*;
+use rand::{rngs::SmallRng, SeedableRng};
+use wasm_bindgen::prelude::*;
+
+/// A maze of pointy-top hexagons laid out in rows, odd rows being shifted half a cell to the right
+#[wasm_bindgen]
+#[derive(Debug)]
+pub struct HexMaze {
+    generator: Generator<HexGrid>,
+}
+#[wasm_bindgen]
+impl HexMaze {
+    pub fn new(width: usize, height: usize, algorithm: Algorithm) -> Self {
+        Self::with_rng(width, height, SmallRng::from_entropy(), algorithm)
+    }
+    pub fn from_seed(width: usize, height: usize, seed: u64, algorithm: Algorithm) -> Self {
+        Self::with_rng(width, height, SmallRng::seed_from_u64(seed), algorithm)
+    }
+
+    pub fn width(&self) -> usize {
+        self.grid().width()
+    }
+    pub fn height(&self) -> usize {
+        self.grid().height()
+    }
+
+    pub fn cells_ptr(&self) -> *const HexCell {
+        self.grid().cells.as_ptr()
+    }
+
+    pub fn get_cell_offset(&self, col: usize, row: usize) -> usize {
+        self.grid().get_cell_offset(col, row)
+    }
+    pub fn get_cell(&self, col: usize, row: usize) -> HexCell {
+        self.grid().get_cell(col, row)
+    }
+
+    pub fn active_cells_ptr(&self) -> *const HexPosition {
+        self.generator.active().as_ptr()
+    }
+    pub fn active_cells_len(&self) -> usize {
+        self.generator.active().len()
+    }
+
+    pub fn gen_step(&mut self) -> bool {
+        self.generator.gen_step()
+    }
+    pub fn generate(&mut self, limit: Option<usize>) -> bool {
+        self.generator.generate(limit)
+    }
+}
+impl HexMaze {
+    fn with_rng(width: usize, height: usize, mut rng: SmallRng, algorithm: Algorithm) -> Self {
+        let mut grid = HexGrid::new(width, height);
+        let algorithm = algorithm.build_generic(&mut grid, &mut rng, HexPosition::new(0, 0));
+        Self {
+            generator: Generator::new(grid, rng, algorithm),
+        }
+    }
+
+    pub fn grid(&self) -> &HexGrid {
+        self.generator.grid()
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct HexGrid {
+    width: usize,
+    height: usize,
+
+    cells: Vec<HexCell>,
+}
+impl HexGrid {
+    pub fn new(width: usize, height: usize) -> Self {
+        let mut default_cell = HexCell::new();
+        default_cell.set_east(true);
+        default_cell.set_south_east(true);
+        default_cell.set_south_west(true);
+        Self {
+            width,
+            height,
+
+            cells: vec![default_cell; width * height],
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get_cell_offset(&self, col: usize, row: usize) -> usize {
+        col + row * self.width
+    }
+    pub fn get_cell(&self, col: usize, row: usize) -> HexCell {
+        self.cells[self.get_cell_offset(col, row)]
+    }
+    pub fn get_cell_mut(&mut self, col: usize, row: usize) -> &mut HexCell {
+        let offset = self.get_cell_offset(col, row);
+        &mut self.cells[offset]
+    }
+
+    /// Returns the position next to `pos` in the given direction, if it is inside the grid
+    pub fn neighbour(&self, pos: HexPosition, dir: HexDirection) -> Option<HexPosition> {
+        let (dq, dr) = dir.axial_offset();
+        let next = HexPosition::from_axial(pos.q() + dq, pos.r() + dr)?;
+        if next.col < self.width && next.row < self.height {
+            Some(next)
+        } else {
+            None
+        }
+    }
+
+    /// Whether there is a wall between `pos` and its neighbour in the given direction,
+    /// the borders of the grid always count as walls
+    pub fn has_wall(&self, pos: HexPosition, dir: HexDirection) -> bool {
+        let next = match self.neighbour(pos, dir) {
+            Some(next) => next,
+            None => return true,
+        };
+        let (owner, dir) = if dir.is_stored() {
+            (pos, dir)
+        } else {
+            (next, dir.opposite())
+        };
+        let cell = self.get_cell(owner.col, owner.row);
+        match dir {
+            HexDirection::East => cell.east(),
+            HexDirection::SouthEast => cell.south_east(),
+            _ => cell.south_west(),
+        }
+    }
+
+    /// Adds or removes the wall between `pos` and its neighbour in the given direction
+    pub fn set_wall(&mut self, pos: HexPosition, dir: HexDirection, wall: bool) {
+        let next = match self.neighbour(pos, dir) {
+            Some(next) => next,
+            None => return,
+        };
+        let (owner, dir) = if dir.is_stored() {
+            (pos, dir)
+        } else {
+            (next, dir.opposite())
+        };
+        let cell = self.get_cell_mut(owner.col, owner.row);
+        match dir {
+            HexDirection::East => cell.set_east(wall),
+            HexDirection::SouthEast => cell.set_south_east(wall),
+            _ => cell.set_south_west(wall),
+        }
+    }
+    /// Removes the wall between `pos` and its neighbour in the given direction
+    pub fn carve(&mut self, pos: HexPosition, dir: HexDirection) {
+        self.set_wall(pos, dir, false);
+    }
+}
+impl Topology for HexGrid {
+    type Cell = HexPosition;
+
+    fn len(&self) -> usize {
+        self.cells.len()
+    }
+    fn index(&self, cell: HexPosition) -> usize {
+        self.get_cell_offset(cell.col, cell.row)
+    }
+    fn cell(&self, index: usize) -> HexPosition {
+        HexPosition::new(index % self.width, index / self.width)
+    }
+    fn neighbours(&self, cell: HexPosition) -> Vec<HexPosition> {
+        HexDirection::ALL
+            .iter()
+            .filter_map(|&dir| self.neighbour(cell, dir))
+            .collect()
+    }
+    fn link(&mut self, a: HexPosition, b: HexPosition) {
+        if let Some(&dir) = HexDirection::ALL
+            .iter()
+            .find(|&&dir| self.neighbour(a, dir) == Some(b))
+        {
+            self.carve(a, dir);
+        }
+    }
+
+    fn visited(&self, cell: HexPosition) -> bool {
+        self.get_cell(cell.col, cell.row).visited()
+    }
+    fn set_visited(&mut self, cell: HexPosition, visited: bool) {
+        self.get_cell_mut(cell.col, cell.row).set_visited(visited);
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HexDirection {
+    East,
+    West,
+    NorthEast,
+    NorthWest,
+    SouthEast,
+    SouthWest,
+}
+impl HexDirection {
+    pub const ALL: [HexDirection; 6] = [
+        HexDirection::East,
+        HexDirection::West,
+        HexDirection::NorthEast,
+        HexDirection::NorthWest,
+        HexDirection::SouthEast,
+        HexDirection::SouthWest,
+    ];
+
+    pub fn opposite(self) -> HexDirection {
+        match self {
+            HexDirection::East => HexDirection::West,
+            HexDirection::West => HexDirection::East,
+            HexDirection::NorthEast => HexDirection::SouthWest,
+            HexDirection::NorthWest => HexDirection::SouthEast,
+            HexDirection::SouthEast => HexDirection::NorthWest,
+            HexDirection::SouthWest => HexDirection::NorthEast,
+        }
+    }
+
+    /// Offset of the neighbour in axial coordinates
+    pub fn axial_offset(self) -> (isize, isize) {
+        match self {
+            HexDirection::East => (1, 0),
+            HexDirection::West => (-1, 0),
+            HexDirection::NorthEast => (1, -1),
+            HexDirection::NorthWest => (0, -1),
+            HexDirection::SouthEast => (0, 1),
+            HexDirection::SouthWest => (-1, 1),
+        }
+    }
+
+    /// Whether the wall in this direction is stored in the cell itself rather than in the neighbour
+    fn is_stored(self) -> bool {
+        matches!(
+            self,
+            HexDirection::East | HexDirection::SouthEast | HexDirection::SouthWest
+        )
+    }
+}
+
+/// Position of a hexagon in offset coordinates, use `q` and `r` for axial coordinates
+#[wasm_bindgen]
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct HexPosition {
+    pub col: usize,
+    pub row: usize,
+}
+#[wasm_bindgen]
+impl HexPosition {
+    pub fn q(&self) -> isize {
+        self.col as isize - (self.row as isize - (self.row & 1) as isize) / 2
+    }
+    pub fn r(&self) -> isize {
+        self.row as isize
+    }
+}
+impl HexPosition {
+    pub fn new(col: usize, row: usize) -> Self {
+        Self { col, row }
+    }
+    /// Converts axial coordinates back to offset ones, if they are not negative
+    pub fn from_axial(q: isize, r: isize) -> Option<Self> {
+        let col = q + (r - (r & 1)) / 2;
+        if col < 0 || r < 0 {
+            return None;
+        }
+        Some(Self::new(col as usize, r as usize))
+    }
+}
+
+bitfield! {
+    #[wasm_bindgen]
+    #[derive(Clone, Copy, Debug)]
+    pub struct HexCell(u8);
+    bool;
+    pub visited, set_visited: 0;
+    pub east, set_east: 1;
+    pub south_east, set_south_east: 2;
+    pub south_west, set_south_west: 3;
+}
+impl HexCell {
+    pub fn new() -> Self {
+        HexCell(0)
+    }
+}
+impl Default for HexCell {
+    fn default() -> Self {
+        Self::new()
+    }
+}
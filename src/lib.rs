@@ -1,3 +1,25 @@
-
+mod algorithm;
+mod generator;
+mod graph;
+mod grid;
+mod hex;
+mod mask;
 mod maze;
-pub use maze::*;
\ No newline at end of file
+mod multi_level;
+mod polar;
+mod render;
+mod topology;
+mod triangle;
+mod world;
+pub use algorithm::*;
+pub use generator::*;
+pub use graph::*;
+pub use grid::*;
+pub use hex::*;
+pub use mask::*;
+pub use maze::*;
+pub use multi_level::*;
+pub use polar::*;
+pub use topology::*;
+pub use triangle::*;
+pub use world::*;
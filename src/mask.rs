@@ -0,0 +1,194 @@
+use wasm_bindgen::prelude::*;
+
+/// Which cells of a rectangle are part of the maze, the others being void
+#[wasm_bindgen]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Mask {
+    width: usize,
+    height: usize,
+
+    cells: Vec<bool>,
+}
+#[wasm_bindgen]
+impl Mask {
+    /// Every cell is enabled
+    #[wasm_bindgen(constructor)]
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+
+            cells: vec![true; width * height],
+        }
+    }
+
+    /// Reads the cells from packed bits, most significant bit first, each row starting on a new
+    /// byte like in PBM images. Set bits are enabled, missing bits are void.
+    pub fn from_bits(width: usize, height: usize, bits: &[u8]) -> Self {
+        let row_len = width.div_ceil(8);
+        let mut mask = Self::new(width, height);
+        for offset in 0..mask.cells.len() {
+            let (x, y) = (offset % width, offset / width);
+            let byte = bits.get(y * row_len + x / 8).copied().unwrap_or(0);
+            mask.cells[offset] = byte & (0x80 >> (x % 8)) != 0;
+        }
+        mask
+    }
+
+    /// Reads the cells from ASCII art, one line per row: spaces and dots are void and any other
+    /// character is enabled. The mask is as wide as the longest line, shorter lines being padded
+    /// with void cells.
+    pub fn from_ascii(art: &str) -> Self {
+        let lines = art.lines().collect::<Vec<_>>();
+        let width = lines
+            .iter()
+            .map(|line| line.chars().count())
+            .max()
+            .unwrap_or(0);
+        let mut mask = Self::new(width, lines.len());
+        for (y, line) in lines.iter().enumerate() {
+            let mut chars = line.chars();
+            for x in 0..width {
+                let enabled = chars.next().is_some_and(|c| c != ' ' && c != '.');
+                mask.set(x, y, enabled);
+            }
+        }
+        mask
+    }
+
+    /// Reads a PBM (P1 or P4) or PGM (P2 or P5) image, black pixels of a PBM and pixels darker
+    /// than middle gray in a PGM are enabled. Returns `None` if the image can't be read.
+    pub fn from_netpbm(data: &[u8]) -> Option<Mask> {
+        let mut reader = NetpbmReader { data, pos: 0 };
+        let magic = reader.token()?;
+        let width = reader.number()?;
+        let height = reader.number()?;
+        let max = match magic {
+            b"P2" | b"P5" => reader.number()?.max(1),
+            _ => 1,
+        };
+
+        // The size is checked against the data left before allocating the mask
+        let len = width.checked_mul(height)?;
+        let mut mask;
+        match magic {
+            b"P1" => {
+                // Each pixel takes at least one byte
+                if reader.remaining() < len {
+                    return None;
+                }
+                mask = Self::new(width, height);
+                for offset in 0..len {
+                    mask.cells[offset] = reader.bit()?;
+                }
+            }
+            b"P2" => {
+                if reader.remaining() < len {
+                    return None;
+                }
+                mask = Self::new(width, height);
+                for offset in 0..len {
+                    mask.cells[offset] = reader.number()? <= max / 2;
+                }
+            }
+            b"P4" => {
+                let bits = reader.raster(width.div_ceil(8).checked_mul(height)?)?;
+                mask = Self::from_bits(width, height, bits);
+            }
+            b"P5" => {
+                let sample_len = if max < 256 { 1 } else { 2 };
+                let samples = reader.raster(len.checked_mul(sample_len)?)?;
+                mask = Self::new(width, height);
+                for (offset, sample) in samples.chunks_exact(sample_len).enumerate() {
+                    let value = sample.iter().fold(0, |acc, &b| (acc << 8) | b as usize);
+                    mask.cells[offset] = value <= max / 2;
+                }
+            }
+            _ => return None,
+        }
+        Some(mask)
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Whether the cell is part of the maze, cells outside of the mask are void
+    pub fn enabled(&self, x: usize, y: usize) -> bool {
+        x < self.width && y < self.height && self.cells[x + y * self.width]
+    }
+    pub fn set(&mut self, x: usize, y: usize, enabled: bool) {
+        if x < self.width && y < self.height {
+            self.cells[x + y * self.width] = enabled;
+        }
+    }
+}
+
+/// Reads the whitespace separated header and the pixels of a netpbm image
+struct NetpbmReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+impl<'a> NetpbmReader<'a> {
+    /// Skips whitespace and comments, which run until the end of the line
+    fn skip_blank(&mut self) {
+        while let Some(&byte) = self.data.get(self.pos) {
+            if byte == b'#' {
+                while self.data.get(self.pos).is_some_and(|&b| b != b'\n') {
+                    self.pos += 1;
+                }
+            } else if byte.is_ascii_whitespace() {
+                self.pos += 1;
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn token(&mut self) -> Option<&'a [u8]> {
+        self.skip_blank();
+        let start = self.pos;
+        while self
+            .data
+            .get(self.pos)
+            .is_some_and(|b| !b.is_ascii_whitespace() && *b != b'#')
+        {
+            self.pos += 1;
+        }
+        if start == self.pos {
+            None
+        } else {
+            Some(&self.data[start..self.pos])
+        }
+    }
+
+    fn number(&mut self) -> Option<usize> {
+        std::str::from_utf8(self.token()?).ok()?.parse().ok()
+    }
+
+    /// Reads a single pixel of a P1 image, where pixels don't need to be separated
+    fn bit(&mut self) -> Option<bool> {
+        self.skip_blank();
+        let byte = *self.data.get(self.pos)?;
+        self.pos += 1;
+        match byte {
+            b'0' => Some(false),
+            b'1' => Some(true),
+            _ => None,
+        }
+    }
+
+    /// Number of bytes left to read
+    fn remaining(&self) -> usize {
+        self.data.len().saturating_sub(self.pos)
+    }
+
+    /// Binary pixels, which start after a single whitespace following the header
+    fn raster(&self, len: usize) -> Option<&'a [u8]> {
+        let start = self.pos + 1;
+        self.data.get(start..start.checked_add(len)?)
+    }
+}
@@ -1,225 +1,315 @@
-use bitfield::*;
-use rand::{rngs::SmallRng, seq::SliceRandom, SeedableRng};
+use crate::{
+    Algorithm, Generator, GeneratorOptions, GraphAlgorithm, Grid, Mask, MazeAlgorithm, MazeCell,
+    OriginShift, Position, Wrap,
+};
+use rand::{rngs::SmallRng, SeedableRng};
 use wasm_bindgen::prelude::*;
 
 #[wasm_bindgen]
 #[derive(Debug)]
 pub struct Maze {
-    width: usize,
-    height: usize,
-
-    cursor: Position,
-    tail: Vec<Position>,
-    cells: Vec<MazeCell>,
-    rng: SmallRng,
+    generator: Generator<Grid>,
+    origin_shift: Option<OriginShift>,
 }
 #[wasm_bindgen]
 impl Maze {
-    pub fn new(width: usize, height: usize) -> Self {
-        let mut default_cell = MazeCell::new();
-        default_cell.set_bottom(true);
-        default_cell.set_right(true);
-        let mut this = Self {
+    pub fn new(width: usize, height: usize, algorithm: Algorithm) -> Self {
+        Self::new_with_options(width, height, algorithm, &GeneratorOptions::new())
+    }
+    pub fn from_seed(width: usize, height: usize, seed: u64, algorithm: Algorithm) -> Self {
+        Self::from_seed_with_options(width, height, seed, algorithm, &GeneratorOptions::new())
+    }
+    pub fn new_with_options(
+        width: usize,
+        height: usize,
+        algorithm: Algorithm,
+        options: &GeneratorOptions,
+    ) -> Self {
+        Self::with_rng(
+            width,
+            height,
+            1,
+            SmallRng::from_entropy(),
+            algorithm,
+            options,
+        )
+    }
+    pub fn from_seed_with_options(
+        width: usize,
+        height: usize,
+        seed: u64,
+        algorithm: Algorithm,
+        options: &GeneratorOptions,
+    ) -> Self {
+        Self::with_rng(
+            width,
+            height,
+            1,
+            SmallRng::seed_from_u64(seed),
+            algorithm,
+            options,
+        )
+    }
+
+    /// Creates a maze of `depth` layers, linked by passages going up and down
+    pub fn new_3d(
+        width: usize,
+        height: usize,
+        depth: usize,
+        algorithm: Algorithm,
+        options: &GeneratorOptions,
+    ) -> Self {
+        Self::with_rng(
+            width,
+            height,
+            depth,
+            SmallRng::from_entropy(),
+            algorithm,
+            options,
+        )
+    }
+    /// Creates a maze of `depth` layers, linked by passages going up and down
+    pub fn from_seed_3d(
+        width: usize,
+        height: usize,
+        depth: usize,
+        seed: u64,
+        algorithm: Algorithm,
+        options: &GeneratorOptions,
+    ) -> Self {
+        Self::with_rng(
             width,
             height,
+            depth,
+            SmallRng::seed_from_u64(seed),
+            algorithm,
+            options,
+        )
+    }
 
-            cursor: Position::new(0, 0),
-            tail: vec![Position::new(0, 0)],
-            cells: vec![default_cell; width * height],
-            rng: SmallRng::from_entropy(),
-        };
-        this.cells[0].set_visited(true);
-        this
-    }
-    pub fn from_seed(width: usize, height: usize, seed: u64) -> Self {
-        let mut default_cell = MazeCell::new();
-        default_cell.set_bottom(true);
-        default_cell.set_right(true);
-        let mut this = Self {
+    /// Grows a tree from each start and joins them, `starts` being a flat list of x, y pairs
+    pub fn new_with_starts(
+        width: usize,
+        height: usize,
+        algorithm: Algorithm,
+        options: &GeneratorOptions,
+        starts: &[usize],
+    ) -> Self {
+        Self::with_starts(
+            width,
+            height,
+            SmallRng::from_entropy(),
+            algorithm,
+            options,
+            starts,
+        )
+    }
+    /// Grows a tree from each start and joins them, `starts` being a flat list of x, y pairs
+    pub fn from_seed_with_starts(
+        width: usize,
+        height: usize,
+        seed: u64,
+        algorithm: Algorithm,
+        options: &GeneratorOptions,
+        starts: &[usize],
+    ) -> Self {
+        Self::with_starts(
             width,
             height,
+            SmallRng::seed_from_u64(seed),
+            algorithm,
+            options,
+            starts,
+        )
+    }
 
-            cursor: Position::new(0, 0),
-            tail: vec![Position::new(0, 0)],
-            cells: vec![default_cell; width * height],
-            rng: SmallRng::seed_from_u64(seed),
-        };
-        this.cells[0].set_visited(true);
-        this
+    /// Creates a maze in the enabled cells of the mask, the other cells being void.
+    /// Each part of the mask cut off from the others by void cells is a separate perfect maze.
+    pub fn new_masked(mask: &Mask, algorithm: Algorithm, options: &GeneratorOptions) -> Self {
+        let grid = Grid::new(mask.width(), mask.height())
+            .with_wrap(options.wrap)
+            .with_mask(mask);
+        Self::with_grid(grid, SmallRng::from_entropy(), algorithm, options)
+    }
+    /// Creates a maze in the enabled cells of the mask, the other cells being void.
+    /// Each part of the mask cut off from the others by void cells is a separate perfect maze.
+    pub fn from_seed_masked(
+        mask: &Mask,
+        seed: u64,
+        algorithm: Algorithm,
+        options: &GeneratorOptions,
+    ) -> Self {
+        let grid = Grid::new(mask.width(), mask.height())
+            .with_wrap(options.wrap)
+            .with_mask(mask);
+        Self::with_grid(grid, SmallRng::seed_from_u64(seed), algorithm, options)
+    }
+
+    /// Creates a maze generated on the graph of its cells, which only offers the algorithms
+    /// working on any graph and falls back to the recursive backtracker for the others
+    pub fn new_on_graph(
+        width: usize,
+        height: usize,
+        algorithm: Algorithm,
+        options: &GeneratorOptions,
+    ) -> Self {
+        let rng = SmallRng::from_entropy();
+        Self::with_graph(width, height, rng, algorithm, options)
+    }
+    /// Creates a maze generated on the graph of its cells, which only offers the algorithms
+    /// working on any graph and falls back to the recursive backtracker for the others
+    pub fn from_seed_on_graph(
+        width: usize,
+        height: usize,
+        seed: u64,
+        algorithm: Algorithm,
+        options: &GeneratorOptions,
+    ) -> Self {
+        let rng = SmallRng::seed_from_u64(seed);
+        Self::with_graph(width, height, rng, algorithm, options)
     }
 
     pub fn width(&self) -> usize {
-        self.width
+        self.grid().width()
     }
     pub fn height(&self) -> usize {
-        self.height
+        self.grid().height()
+    }
+    pub fn depth(&self) -> usize {
+        self.grid().depth()
+    }
+    pub fn wrap(&self) -> Wrap {
+        self.grid().wrap()
     }
 
     pub fn cells_ptr(&self) -> *const MazeCell {
-        self.cells.as_ptr()
+        self.grid().cells().as_ptr()
     }
 
     pub fn get_cell_offset(&self, x: usize, y: usize) -> usize {
-        x + y * self.width
+        self.grid().get_cell_offset(x, y)
     }
     pub fn get_cell(&self, x: usize, y: usize) -> MazeCell {
-        self.cells[self.get_cell_offset(x, y)]
+        self.grid().get_cell(x, y)
     }
-    fn get_cell_mut(&mut self, x: usize, y: usize) -> &mut MazeCell {
-        let offset = self.get_cell_offset(x, y);
-        &mut self.cells[offset]
+    pub fn get_cell_offset_3d(&self, x: usize, y: usize, z: usize) -> usize {
+        self.grid().offset(Position::new_3d(x, y, z))
+    }
+    pub fn get_cell_3d(&self, x: usize, y: usize, z: usize) -> MazeCell {
+        self.grid().cell(Position::new_3d(x, y, z))
     }
 
-    pub fn gen_step(&mut self) -> bool {
-        let mut next_pos = [
-            Direction::Left,
-            Direction::Right,
-            Direction::Top,
-            Direction::Bottom,
-        ];
-        next_pos.shuffle(&mut self.rng);
-        let mut dest: Option<Direction> = None;
-
-        for dir in next_pos.iter() {
-            match dir {
-                Direction::Bottom => {
-                    if self.cursor.y >= self.height-1 {
-                        continue;
-                    };
-                    let pos = Position::new(self.cursor.x, self.cursor.y + 1);
-                    if self.get_cell(pos.x, pos.y).visited() {
-                        continue;
-                    }
-                    dest = Some(Direction::Bottom);
-                    break;
-                }
-                Direction::Left => {
-                    if self.cursor.x == 0 {
-                        continue;
-                    };
-                    let pos = Position::new(self.cursor.x - 1, self.cursor.y);
-                    if self.get_cell(pos.x, pos.y).visited() {
-                        continue;
-                    }
-                    dest = Some(Direction::Left);
-                    break;
-                }
-                Direction::Right => {
-                    if self.cursor.x >= self.width-1 {
-                        continue;
-                    };
-                    let pos = Position::new(self.cursor.x + 1, self.cursor.y);
-                    if self.get_cell(pos.x, pos.y).visited() {
-                        continue;
-                    }
-                    dest = Some(Direction::Right);
-                    break;
-                }
-                Direction::Top => {
-                    if self.cursor.y == 0 {
-                        continue;
-                    };
-                    let pos = Position::new(self.cursor.x, self.cursor.y - 1);
-                    if self.get_cell(pos.x, pos.y).visited() {
-                        continue;
-                    }
-                    dest = Some(Direction::Top);
-                    break;
-                }
-            }
-        }
-
-        match dest {
-            Some(dir) => {
-                match dir {
-                    Direction::Bottom => {
-                        self.get_cell_mut(self.cursor.x, self.cursor.y).set_bottom(false);
-                        dir.apply(&mut self.cursor);
-                    }
-                    Direction::Right => {
-                        self.get_cell_mut(self.cursor.x, self.cursor.y).set_right(false);
-                        dir.apply(&mut self.cursor);
-                    }
-                    Direction::Top => {
-                        dir.apply(&mut self.cursor);
-                        self.get_cell_mut(self.cursor.x, self.cursor.y).set_bottom(false);
-                    }
-                    Direction::Left => {
-                        dir.apply(&mut self.cursor);
-                        self.get_cell_mut(self.cursor.x, self.cursor.y).set_right(false);
-                    }
-                }
-                self.get_cell_mut(self.cursor.x, self.cursor.y).set_visited(true);
-                self.tail.push(self.cursor.clone());
-            }
-            None => match self.tail.pop() {
-                None => return true,
-                Some(pos) => self.cursor = pos,
-            },
-        }
+    /// Pointer to the active positions, each made of three `usize` for x, y and z
+    pub fn active_cells_ptr(&self) -> *const Position {
+        self.generator.active().as_ptr()
+    }
+    pub fn active_cells_len(&self) -> usize {
+        self.generator.active().len()
+    }
 
-        false
+    pub fn gen_step(&mut self) -> bool {
+        self.generator.gen_step()
     }
     pub fn generate(&mut self, limit: Option<usize>) -> bool {
-        for _ in 0..limit.unwrap_or(usize::MAX) {
-            if self.gen_step() {
-                return true;
-            }
-        }
-        false
+        self.generator.generate(limit)
     }
-}
 
-#[derive(Clone, Copy, Debug)]
-enum Direction {
-    Top,
-    Right,
-    Left,
-    Bottom,
-}
-impl Direction {
-    pub fn apply(&self, pos: &mut Position) {
-        match self {
-            Direction::Top => {
-                pos.y -= 1;
-            }
-            Direction::Right => {
-                pos.x += 1;
-            }
-            Direction::Left => {
-                pos.x -= 1;
-            }
-            Direction::Bottom => {
-                pos.y += 1;
-            }
+    /// Removes a fraction (between 0 and 1) of the dead ends of the maze, adding loops to it
+    pub fn braid(&mut self, factor: f64) {
+        let (grid, rng) = self.generator.parts_mut();
+        crate::braid(grid, rng, factor);
+    }
+
+    /// Rewires the finished maze by moving its origin to a random neighbour, keeping it perfect.
+    /// Returns false without changing anything while the maze is still being generated.
+    pub fn shift_step(&mut self) -> bool {
+        if !self.generator.is_finished() {
+            return false;
         }
+        let (grid, rng) = self.generator.parts_mut();
+        let origin_shift = self.origin_shift.get_or_insert_with(|| {
+            let origin = grid
+                .positions()
+                .find(|&pos| !grid.is_void(pos) && !grid.cell(pos).crossing())
+                .unwrap_or_else(|| Position::new(0, 0));
+            OriginShift::new(grid, origin)
+        });
+        origin_shift.shift(grid, rng);
+        true
+    }
+    /// Current origin of the shifting maze, once `shift_step` has been called
+    pub fn origin(&self) -> Option<Position> {
+        self.origin_shift.as_ref().map(OriginShift::origin)
     }
 }
+impl Maze {
+    fn with_rng(
+        width: usize,
+        height: usize,
+        depth: usize,
+        rng: SmallRng,
+        algorithm: Algorithm,
+        options: &GeneratorOptions,
+    ) -> Self {
+        let grid = Grid::new_3d(width, height, depth).with_wrap(options.wrap);
+        Self::with_grid(grid, rng, algorithm, options)
+    }
 
-#[wasm_bindgen]
-#[derive(Clone, Debug)]
-pub struct Position {
-    x: usize,
-    y: usize,
-}
-impl Position {
-    pub fn new(x: usize, y: usize) -> Self {
-        Self { x, y }
+    fn with_grid(
+        mut grid: Grid,
+        mut rng: SmallRng,
+        algorithm: Algorithm,
+        options: &GeneratorOptions,
+    ) -> Self {
+        let algorithm = algorithm.build(&mut grid, &mut rng, options);
+        Self::with_algorithm(grid, rng, algorithm)
     }
-}
 
-bitfield! {
-    #[wasm_bindgen]
-    #[derive(Clone, Copy, Debug)]
-    pub struct MazeCell(u8);
-    bool;
-    // The fields default to u16
-    pub visited, set_visited: 0;
-    pub right, set_right: 1;
-    pub bottom, set_bottom: 2;
-}
-impl MazeCell {
-    pub fn new() -> Self {
-        MazeCell(0)
+    fn with_graph(
+        width: usize,
+        height: usize,
+        mut rng: SmallRng,
+        algorithm: Algorithm,
+        options: &GeneratorOptions,
+    ) -> Self {
+        let mut grid = Grid::new(width, height).with_wrap(options.wrap);
+        let start = Position::new(
+            options.start_x.min(width.saturating_sub(1)),
+            options.start_y.min(height.saturating_sub(1)),
+        );
+        let algorithm = GraphAlgorithm::new(&mut grid, &mut rng, algorithm, start);
+        Self::with_algorithm(grid, rng, Box::new(algorithm))
+    }
+
+    fn with_starts(
+        width: usize,
+        height: usize,
+        mut rng: SmallRng,
+        algorithm: Algorithm,
+        options: &GeneratorOptions,
+        starts: &[usize],
+    ) -> Self {
+        let starts = starts
+            .chunks_exact(2)
+            .map(|pos| Position::new(pos[0], pos[1]))
+            .collect::<Vec<_>>();
+        let mut grid = Grid::new(width, height).with_wrap(options.wrap);
+        let algorithm = algorithm.build_with_starts(&mut grid, &mut rng, options, &starts);
+        Self::with_algorithm(grid, rng, algorithm)
+    }
+
+    /// Creates a maze driven by a custom algorithm, which must already be initialized for `grid`
+    pub fn with_algorithm(grid: Grid, rng: SmallRng, algorithm: Box<dyn MazeAlgorithm>) -> Self {
+        Self {
+            generator: Generator::new(grid, rng, algorithm),
+            origin_shift: None,
+        }
+    }
+
+    pub fn grid(&self) -> &Grid {
+        self.generator.grid()
+    }
+    pub(crate) fn grid_mut(&mut self) -> &mut Grid {
+        self.generator.parts_mut().0
     }
 }
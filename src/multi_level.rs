@@ -0,0 +1,344 @@
+use crate::{Algorithm, Direction, GeneratorOptions, Maze, MazeCell, Position};
+use rand::{rngs::SmallRng, seq::SliceRandom, Rng, SeedableRng};
+use std::collections::{HashMap, VecDeque};
+use wasm_bindgen::prelude::*;
+
+/// How the floors of a `MultiLevelMaze` are linked to each other
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LinkKind {
+    /// Links a cell to the cell at the same place on the next floor
+    Stairs,
+    /// Links any two cells of two different floors
+    Teleporter,
+}
+
+/// Position of a cell on one of the floors of a `MultiLevelMaze`
+#[wasm_bindgen]
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct FloorPosition {
+    pub floor: usize,
+    pub pos: Position,
+}
+impl FloorPosition {
+    pub fn new(floor: usize, pos: Position) -> Self {
+        Self { floor, pos }
+    }
+}
+
+/// Stairs or teleporter between two cells of different floors, usable both ways
+#[wasm_bindgen]
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Link {
+    pub from: FloorPosition,
+    pub to: FloorPosition,
+}
+
+/// Several flat mazes stacked as the floors of a building and linked by stairs or teleporters.
+/// The floors are generated one after the other, then a link is added at each step: the first
+/// ones join the floors into a single maze, and every extra link closes a passage on the loop it
+/// creates, so that there is still a single path between any two cells. This needs perfect floors,
+/// so the algorithm and options are first adjusted by `Algorithm::perfect`.
+#[wasm_bindgen]
+#[derive(Debug)]
+pub struct MultiLevelMaze {
+    floors: Vec<Maze>,
+    current: usize,
+    kind: LinkKind,
+    link_count: usize,
+    links: Vec<Link>,
+    linked: HashMap<FloorPosition, FloorPosition>,
+    rng: SmallRng,
+}
+#[wasm_bindgen]
+impl MultiLevelMaze {
+    /// Creates `floors` floors joined by `links` links, at least enough to connect every floor
+    pub fn new(
+        width: usize,
+        height: usize,
+        floors: usize,
+        links: usize,
+        kind: LinkKind,
+        algorithm: Algorithm,
+        options: &GeneratorOptions,
+    ) -> Self {
+        let rng = SmallRng::from_entropy();
+        Self::with_rng(width, height, floors, links, kind, rng, algorithm, options)
+    }
+    /// Creates `floors` floors joined by `links` links, at least enough to connect every floor
+    #[allow(clippy::too_many_arguments)]
+    pub fn from_seed(
+        width: usize,
+        height: usize,
+        floors: usize,
+        links: usize,
+        kind: LinkKind,
+        seed: u64,
+        algorithm: Algorithm,
+        options: &GeneratorOptions,
+    ) -> Self {
+        let rng = SmallRng::seed_from_u64(seed);
+        Self::with_rng(width, height, floors, links, kind, rng, algorithm, options)
+    }
+
+    pub fn floors(&self) -> usize {
+        self.floors.len()
+    }
+    pub fn width(&self) -> usize {
+        self.floors[0].width()
+    }
+    pub fn height(&self) -> usize {
+        self.floors[0].height()
+    }
+
+    pub fn cells_ptr(&self, floor: usize) -> *const MazeCell {
+        self.floors[floor].cells_ptr()
+    }
+
+    pub fn get_cell_offset(&self, x: usize, y: usize) -> usize {
+        self.floors[0].get_cell_offset(x, y)
+    }
+    pub fn get_cell(&self, floor: usize, x: usize, y: usize) -> MazeCell {
+        self.floors[floor].get_cell(x, y)
+    }
+
+    pub fn links_ptr(&self) -> *const Link {
+        self.links.as_ptr()
+    }
+    pub fn links_len(&self) -> usize {
+        self.links.len()
+    }
+    /// The other end of the link starting from the cell, if there is one
+    pub fn get_link(&self, floor: usize, x: usize, y: usize) -> Option<FloorPosition> {
+        let pos = FloorPosition::new(floor, Position::new(x, y));
+        self.linked.get(&pos).copied()
+    }
+
+    /// The floor being generated, `None` once every floor is done
+    pub fn active_floor(&self) -> Option<usize> {
+        if self.current < self.floors.len() {
+            Some(self.current)
+        } else {
+            None
+        }
+    }
+    pub fn active_cells_ptr(&self) -> *const Position {
+        match self.floors.get(self.current) {
+            Some(floor) => floor.active_cells_ptr(),
+            None => std::ptr::null(),
+        }
+    }
+    pub fn active_cells_len(&self) -> usize {
+        match self.floors.get(self.current) {
+            Some(floor) => floor.active_cells_len(),
+            None => 0,
+        }
+    }
+
+    pub fn gen_step(&mut self) -> bool {
+        if let Some(floor) = self.floors.get_mut(self.current) {
+            if floor.gen_step() {
+                self.current += 1;
+            }
+            return false;
+        }
+        if self.links.len() < self.link_count && !self.add_link() {
+            // Every cell that could hold a link already has one
+            self.link_count = self.links.len();
+        }
+        self.links.len() >= self.link_count
+    }
+    pub fn generate(&mut self, limit: Option<usize>) -> bool {
+        match limit {
+            None => {
+                for floor in self.floors[self.current..].iter_mut() {
+                    floor.generate(None);
+                }
+                self.current = self.floors.len();
+                while !self.gen_step() {}
+                true
+            }
+            Some(limit) => {
+                for _ in 0..limit {
+                    if self.gen_step() {
+                        return true;
+                    }
+                }
+                false
+            }
+        }
+    }
+}
+impl MultiLevelMaze {
+    #[allow(clippy::too_many_arguments)]
+    fn with_rng(
+        width: usize,
+        height: usize,
+        floors: usize,
+        links: usize,
+        kind: LinkKind,
+        mut rng: SmallRng,
+        algorithm: Algorithm,
+        options: &GeneratorOptions,
+    ) -> Self {
+        let (algorithm, options) = algorithm.perfect(options);
+        let floors = (0..floors.max(1))
+            .map(|_| Maze::from_seed_with_options(width, height, rng.gen(), algorithm, &options))
+            .collect::<Vec<_>>();
+        Self {
+            link_count: links.max(floors.len() - 1),
+            floors,
+            current: 0,
+            kind,
+            links: vec![],
+            linked: HashMap::new(),
+            rng,
+        }
+    }
+
+    pub fn floor(&self, floor: usize) -> &Maze {
+        &self.floors[floor]
+    }
+    pub fn links(&self) -> &[Link] {
+        &self.links
+    }
+
+    /// Whether the cell was carved into the maze and has no link yet
+    fn is_free(&self, pos: FloorPosition) -> bool {
+        self.floors[pos.floor].grid().visited(pos.pos) && !self.linked.contains_key(&pos)
+    }
+    /// Free cells of the given floors
+    fn free_cells(&self, floors: impl Iterator<Item = usize>) -> Vec<FloorPosition> {
+        floors
+            .flat_map(|floor| {
+                let grid = self.floors[floor].grid();
+                grid.positions()
+                    .map(move |pos| FloorPosition::new(floor, pos))
+            })
+            .filter(|&pos| self.is_free(pos))
+            .collect()
+    }
+
+    /// Picks the two ends of the next link: the first links join each floor to one of the
+    /// previous ones, the extra ones can be anywhere
+    fn pick_ends(&mut self) -> Option<(FloorPosition, FloorPosition)> {
+        let count = self.floors.len();
+        let joining = self.links.len() + 1 < count;
+        match self.kind {
+            LinkKind::Stairs => {
+                let lower_floors = if joining {
+                    self.links.len()..self.links.len() + 1
+                } else {
+                    0..count - 1
+                };
+                let ends = self
+                    .free_cells(lower_floors)
+                    .into_iter()
+                    .map(|from| (from, FloorPosition::new(from.floor + 1, from.pos)))
+                    .filter(|&(_, to)| self.is_free(to))
+                    .collect::<Vec<_>>();
+                ends.choose(&mut self.rng).copied()
+            }
+            LinkKind::Teleporter => {
+                let (from, to) = if joining {
+                    let next = self.links.len() + 1;
+                    let from = *self.free_cells(0..next).choose(&mut self.rng)?;
+                    (from, self.free_cells(next..next + 1))
+                } else {
+                    let from = *self.free_cells(0..count).choose(&mut self.rng)?;
+                    let others = (0..count).filter(|&floor| floor != from.floor);
+                    (from, self.free_cells(others))
+                };
+                Some((from, *to.choose(&mut self.rng)?))
+            }
+        }
+    }
+
+    /// Adds a link between two free cells, returns false if there is no room for it or if the
+    /// loop it would create can't be broken
+    fn add_link(&mut self) -> bool {
+        if self.floors.len() < 2 {
+            return false;
+        }
+        let (from, to) = match self.pick_ends() {
+            Some(ends) => ends,
+            None => return false,
+        };
+
+        if !self.break_loop(from, to) {
+            return false;
+        }
+        self.linked.insert(from, to);
+        self.linked.insert(to, from);
+        self.links.push(Link { from, to });
+        true
+    }
+
+    /// Closes a random passage on the path between the two cells, if they are already connected.
+    /// The walls of crossings are never closed so that their two passages stay straight, returns
+    /// false if the path has no other passage to close.
+    fn break_loop(&mut self, from: FloorPosition, to: FloorPosition) -> bool {
+        let first = self.floors[0].grid();
+        let floor_len = first.len();
+        let index = |pos: FloorPosition| pos.floor * floor_len + first.offset(pos.pos);
+
+        // The cell each cell was reached from, and the side it was left through unless it was a link
+        let mut parents: Vec<Option<(FloorPosition, Option<Direction>)>> =
+            vec![None; floor_len * self.floors.len()];
+        let mut seen = vec![false; floor_len * self.floors.len()];
+        seen[index(from)] = true;
+        let mut queue = VecDeque::new();
+        queue.push_back(from);
+        while let Some(current) = queue.pop_front() {
+            if current == to {
+                break;
+            }
+            let grid = self.floors[current.floor].grid();
+            let mut next_cells = grid
+                .directions()
+                .iter()
+                .filter_map(|&dir| {
+                    let next = grid.passage(current.pos, dir)?;
+                    Some((FloorPosition::new(current.floor, next), Some(dir)))
+                })
+                .collect::<Vec<_>>();
+            if let Some(&other) = self.linked.get(&current) {
+                next_cells.push((other, None));
+            }
+            for (next, dir) in next_cells {
+                let next_index = index(next);
+                if !seen[next_index] {
+                    seen[next_index] = true;
+                    parents[next_index] = Some((current, dir));
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        if !seen[index(to)] {
+            return true;
+        }
+        let mut passages = vec![];
+        let mut current = to;
+        while let Some((parent, dir)) = parents[index(current)] {
+            if let Some(dir) = dir {
+                let grid = self.floors[parent.floor].grid();
+                let crossing =
+                    grid.cell(parent.pos).crossing() || grid.cell(current.pos).crossing();
+                if grid.has_wall(parent.pos, dir) || !crossing {
+                    passages.push((parent, dir));
+                }
+            }
+            current = parent;
+        }
+        match passages.choose(&mut self.rng) {
+            Some(&(pos, dir)) => {
+                self.floors[pos.floor].grid_mut().cut(pos.pos, dir);
+                true
+            }
+            None => false,
+        }
+    }
+}
@@ -0,0 +1,255 @@
+use crate::{Algorithm, Generator, Topology};
+use bitfield::*;
+use rand::{rngs::SmallRng, SeedableRng};
+use std::f64::consts::PI;
+use wasm_bindgen::prelude::*;
+
+/// A circular maze made of concentric rings around a single center cell. Rings are split into more
+/// cells as they get bigger, so that cells keep roughly the same width as the height of a ring.
+/// Cells are numbered clockwise within their ring.
+#[wasm_bindgen]
+#[derive(Debug)]
+pub struct PolarMaze {
+    generator: Generator<PolarGrid>,
+}
+#[wasm_bindgen]
+impl PolarMaze {
+    pub fn new(rings: usize, algorithm: Algorithm) -> Self {
+        Self::with_rng(rings, SmallRng::from_entropy(), algorithm)
+    }
+    pub fn from_seed(rings: usize, seed: u64, algorithm: Algorithm) -> Self {
+        Self::with_rng(rings, SmallRng::seed_from_u64(seed), algorithm)
+    }
+
+    /// Number of rings, including the center cell
+    pub fn rings(&self) -> usize {
+        self.grid().rings()
+    }
+    /// Number of cells in the ring
+    pub fn ring_len(&self, ring: usize) -> usize {
+        self.grid().ring_len(ring)
+    }
+
+    pub fn cells_ptr(&self) -> *const PolarCell {
+        self.grid().cells.as_ptr()
+    }
+
+    pub fn get_cell_offset(&self, ring: usize, index: usize) -> usize {
+        self.grid().get_cell_offset(ring, index)
+    }
+    pub fn get_cell(&self, ring: usize, index: usize) -> PolarCell {
+        self.grid().get_cell(ring, index)
+    }
+
+    pub fn active_cells_ptr(&self) -> *const PolarPosition {
+        self.generator.active().as_ptr()
+    }
+    pub fn active_cells_len(&self) -> usize {
+        self.generator.active().len()
+    }
+
+    pub fn gen_step(&mut self) -> bool {
+        self.generator.gen_step()
+    }
+    pub fn generate(&mut self, limit: Option<usize>) -> bool {
+        self.generator.generate(limit)
+    }
+}
+impl PolarMaze {
+    fn with_rng(rings: usize, mut rng: SmallRng, algorithm: Algorithm) -> Self {
+        let mut grid = PolarGrid::new(rings);
+        let algorithm = algorithm.build_generic(&mut grid, &mut rng, PolarPosition::new(0, 0));
+        Self {
+            generator: Generator::new(grid, rng, algorithm),
+        }
+    }
+
+    pub fn grid(&self) -> &PolarGrid {
+        self.generator.grid()
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct PolarGrid {
+    ring_lens: Vec<usize>,
+    ring_offsets: Vec<usize>,
+
+    cells: Vec<PolarCell>,
+}
+impl PolarGrid {
+    pub fn new(rings: usize) -> Self {
+        let mut ring_lens = vec![];
+        let mut ring_offsets = vec![];
+        let mut len = 0;
+        for ring in 0..rings {
+            let ring_len = match ring_lens.last() {
+                None => 1,
+                Some(&previous) => {
+                    // Each cell of the ring below is split into as many cells as fit on the ring
+                    let circumference = 2.0 * PI * ring as f64;
+                    let ratio = (circumference / previous as f64).round().max(1.0);
+                    previous * ratio as usize
+                }
+            };
+            ring_lens.push(ring_len);
+            ring_offsets.push(len);
+            len += ring_len;
+        }
+
+        let mut default_cell = PolarCell::new();
+        default_cell.set_clockwise(true);
+        default_cell.set_inward(true);
+        Self {
+            ring_lens,
+            ring_offsets,
+
+            cells: vec![default_cell; len],
+        }
+    }
+
+    pub fn rings(&self) -> usize {
+        self.ring_lens.len()
+    }
+    pub fn ring_len(&self, ring: usize) -> usize {
+        self.ring_lens[ring]
+    }
+
+    pub fn get_cell_offset(&self, ring: usize, index: usize) -> usize {
+        self.ring_offsets[ring] + index
+    }
+    pub fn get_cell(&self, ring: usize, index: usize) -> PolarCell {
+        self.cells[self.get_cell_offset(ring, index)]
+    }
+    pub fn get_cell_mut(&mut self, ring: usize, index: usize) -> &mut PolarCell {
+        let offset = self.get_cell_offset(ring, index);
+        &mut self.cells[offset]
+    }
+
+    pub fn clockwise(&self, pos: PolarPosition) -> Option<PolarPosition> {
+        let len = self.ring_len(pos.ring);
+        if len < 2 {
+            return None;
+        }
+        Some(PolarPosition::new(pos.ring, (pos.index + 1) % len))
+    }
+    pub fn counter_clockwise(&self, pos: PolarPosition) -> Option<PolarPosition> {
+        let len = self.ring_len(pos.ring);
+        if len < 2 {
+            return None;
+        }
+        Some(PolarPosition::new(pos.ring, (pos.index + len - 1) % len))
+    }
+    pub fn inward(&self, pos: PolarPosition) -> Option<PolarPosition> {
+        if pos.ring == 0 {
+            return None;
+        }
+        let ratio = self.ring_len(pos.ring) / self.ring_len(pos.ring - 1);
+        Some(PolarPosition::new(pos.ring - 1, pos.index / ratio))
+    }
+    /// Cells of the next ring touching this one, from the most counter-clockwise to the most clockwise
+    pub fn outward(&self, pos: PolarPosition) -> Vec<PolarPosition> {
+        if pos.ring + 1 >= self.rings() {
+            return vec![];
+        }
+        let ratio = self.ring_len(pos.ring + 1) / self.ring_len(pos.ring);
+        (pos.index * ratio..(pos.index + 1) * ratio)
+            .map(|index| PolarPosition::new(pos.ring + 1, index))
+            .collect()
+    }
+
+    /// Adds or removes the wall between two neighbouring cells
+    pub fn set_wall(&mut self, a: PolarPosition, b: PolarPosition, wall: bool) {
+        if a.ring == b.ring {
+            let from = if self.clockwise(a) == Some(b) { a } else { b };
+            self.get_cell_mut(from.ring, from.index).set_clockwise(wall);
+        } else {
+            let outer = if a.ring > b.ring { a } else { b };
+            self.get_cell_mut(outer.ring, outer.index).set_inward(wall);
+        }
+    }
+    /// Whether there is a wall between two neighbouring cells
+    pub fn has_wall(&self, a: PolarPosition, b: PolarPosition) -> bool {
+        if a.ring == b.ring {
+            let from = if self.clockwise(a) == Some(b) { a } else { b };
+            self.get_cell(from.ring, from.index).clockwise()
+        } else {
+            let outer = if a.ring > b.ring { a } else { b };
+            self.get_cell(outer.ring, outer.index).inward()
+        }
+    }
+}
+impl Topology for PolarGrid {
+    type Cell = PolarPosition;
+
+    fn len(&self) -> usize {
+        self.cells.len()
+    }
+    fn index(&self, cell: PolarPosition) -> usize {
+        self.get_cell_offset(cell.ring, cell.index)
+    }
+    fn cell(&self, index: usize) -> PolarPosition {
+        let ring = self
+            .ring_offsets
+            .iter()
+            .rposition(|&offset| offset <= index)
+            .unwrap();
+        PolarPosition::new(ring, index - self.ring_offsets[ring])
+    }
+    fn neighbours(&self, cell: PolarPosition) -> Vec<PolarPosition> {
+        let mut neighbours = self.outward(cell);
+        neighbours.extend(self.inward(cell));
+        neighbours.extend(self.clockwise(cell));
+        if let Some(next) = self.counter_clockwise(cell) {
+            if !neighbours.contains(&next) {
+                neighbours.push(next);
+            }
+        }
+        neighbours
+    }
+    fn link(&mut self, a: PolarPosition, b: PolarPosition) {
+        self.set_wall(a, b, false);
+    }
+
+    fn visited(&self, cell: PolarPosition) -> bool {
+        self.get_cell(cell.ring, cell.index).visited()
+    }
+    fn set_visited(&mut self, cell: PolarPosition, visited: bool) {
+        self.get_cell_mut(cell.ring, cell.index)
+            .set_visited(visited);
+    }
+}
+
+#[wasm_bindgen]
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct PolarPosition {
+    pub ring: usize,
+    pub index: usize,
+}
+impl PolarPosition {
+    pub fn new(ring: usize, index: usize) -> Self {
+        Self { ring, index }
+    }
+}
+
+bitfield! {
+    #[wasm_bindgen]
+    #[derive(Clone, Copy, Debug)]
+    pub struct PolarCell(u8);
+    bool;
+    pub visited, set_visited: 0;
+    // Wall with the next cell of the ring, clockwise
+    pub clockwise, set_clockwise: 1;
+    // Wall with the cell of the ring below
+    pub inward, set_inward: 2;
+}
+impl PolarCell {
+    pub fn new() -> Self {
+        PolarCell(0)
+    }
+}
+impl Default for PolarCell {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,65 @@
+use crate::{Direction, Grid, Maze, Position};
+use std::fmt::Write;
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen]
+impl Maze {
+    /// Draws the walls of a layer of the maze as an SVG image. Each cell is drawn `inset` units
+    /// inside its square and joined to its neighbours by short corridors, which lets tunnels
+    /// show as gaps under the crossings; void cells and passages to other layers are not drawn.
+    pub fn to_svg(&self, layer: usize, cell_size: f64, inset: f64) -> String {
+        render_svg(self.grid(), layer, cell_size, inset)
+    }
+}
+
+fn render_svg(grid: &Grid, layer: usize, cell_size: f64, inset: f64) -> String {
+    let inset = inset.clamp(0.0, cell_size / 2.0);
+    let mut path = String::new();
+    let mut line = |from: (f64, f64), to: (f64, f64)| {
+        let _ = write!(path, "M{} {}L{} {}", from.0, from.1, to.0, to.1);
+    };
+
+    for y in 0..grid.height() {
+        for x in 0..grid.width() {
+            let pos = Position::new_3d(x, y, layer);
+            if layer >= grid.depth() || grid.is_void(pos) {
+                continue;
+            }
+            let crossing = grid.cell(pos).crossing();
+            let (x0, y0) = (x as f64 * cell_size, y as f64 * cell_size);
+            let (x3, y3) = (x0 + cell_size, y0 + cell_size);
+            let (x1, y1, x2, y2) = (x0 + inset, y0 + inset, x3 - inset, y3 - inset);
+
+            // Each side is the inner edge of the cell and the corridor from its two ends to the border
+            let sides = [
+                (Direction::Top, (x1, y1), (x2, y1), (0.0, -inset)),
+                (Direction::Right, (x2, y1), (x2, y2), (inset, 0.0)),
+                (Direction::Bottom, (x1, y2), (x2, y2), (0.0, inset)),
+                (Direction::Left, (x1, y1), (x1, y2), (-inset, 0.0)),
+            ];
+            for &(dir, a, b, (dx, dy)) in sides.iter() {
+                // The under-passage of a crossing goes through walls that are still drawn
+                let under =
+                    crossing && grid.has_wall(pos, dir) && grid.neighbour(pos, dir).is_some();
+                let open = under || grid.passage(pos, dir).is_some();
+                if !open || under {
+                    line(a, b);
+                }
+                if open && inset > 0.0 {
+                    line(a, (a.0 + dx, a.1 + dy));
+                    line(b, (b.0 + dx, b.1 + dy));
+                }
+            }
+        }
+    }
+
+    format!(
+        concat!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}">"#,
+            r#"<path d="{}" fill="none" stroke="black" stroke-linecap="square"/></svg>"#
+        ),
+        path,
+        w = grid.width() as f64 * cell_size,
+        h = grid.height() as f64 * cell_size,
+    )
+}
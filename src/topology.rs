@@ -0,0 +1,65 @@
+use crate::{Grid, Position};
+use std::fmt;
+
+/// The cells of a maze and how they neighbour each other, which is all the generic algorithms
+/// need to know to carve passages regardless of the shape of the cells
+pub trait Topology {
+    type Cell: Copy + Eq + fmt::Debug;
+
+    /// Number of cells
+    fn len(&self) -> usize;
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    /// Unique index of the cell between 0 and `len`
+    fn index(&self, cell: Self::Cell) -> usize;
+    /// Cell with the given index
+    fn cell(&self, index: usize) -> Self::Cell;
+    /// Cells a passage can be carved to from `cell`
+    fn neighbours(&self, cell: Self::Cell) -> Vec<Self::Cell>;
+    /// Removes the wall between two neighbouring cells
+    fn link(&mut self, a: Self::Cell, b: Self::Cell);
+
+    fn visited(&self, cell: Self::Cell) -> bool;
+    fn set_visited(&mut self, cell: Self::Cell, visited: bool);
+}
+
+impl Topology for Grid {
+    type Cell = Position;
+
+    fn len(&self) -> usize {
+        Grid::len(self)
+    }
+    fn index(&self, cell: Position) -> usize {
+        self.offset(cell)
+    }
+    fn cell(&self, index: usize) -> Position {
+        Position::new_3d(
+            index % self.width(),
+            index / self.width() % self.height(),
+            index / (self.width() * self.height()),
+        )
+    }
+    fn neighbours(&self, cell: Position) -> Vec<Position> {
+        self.directions()
+            .iter()
+            .filter_map(|&dir| self.neighbour(cell, dir))
+            .collect()
+    }
+    fn link(&mut self, a: Position, b: Position) {
+        if let Some(&dir) = self
+            .directions()
+            .iter()
+            .find(|&&dir| self.neighbour(a, dir) == Some(b))
+        {
+            self.carve(a, dir);
+        }
+    }
+
+    fn visited(&self, cell: Position) -> bool {
+        Grid::visited(self, cell)
+    }
+    fn set_visited(&mut self, cell: Position, visited: bool) {
+        Grid::set_visited(self, cell, visited)
+    }
+}
@@ -0,0 +1,245 @@
+use crate::{Algorithm, Generator, Position, Topology};
+use bitfield::*;
+use rand::{rngs::SmallRng, SeedableRng};
+use wasm_bindgen::prelude::*;
+
+/// A maze of triangles laid out in rows, alternating between pointing up and pointing down.
+/// The cell at (0, 0) points up, and so does every cell where `x + y` is even.
+#[wasm_bindgen]
+#[derive(Debug)]
+pub struct TriangleMaze {
+    generator: Generator<TriangleGrid>,
+}
+#[wasm_bindgen]
+impl TriangleMaze {
+    pub fn new(width: usize, height: usize, algorithm: Algorithm) -> Self {
+        Self::with_rng(width, height, SmallRng::from_entropy(), algorithm)
+    }
+    pub fn from_seed(width: usize, height: usize, seed: u64, algorithm: Algorithm) -> Self {
+        Self::with_rng(width, height, SmallRng::seed_from_u64(seed), algorithm)
+    }
+
+    pub fn width(&self) -> usize {
+        self.grid().width()
+    }
+    pub fn height(&self) -> usize {
+        self.grid().height()
+    }
+
+    pub fn cells_ptr(&self) -> *const TriangleCell {
+        self.grid().cells.as_ptr()
+    }
+
+    pub fn get_cell_offset(&self, x: usize, y: usize) -> usize {
+        self.grid().get_cell_offset(x, y)
+    }
+    pub fn get_cell(&self, x: usize, y: usize) -> TriangleCell {
+        self.grid().get_cell(x, y)
+    }
+    pub fn points_up(&self, x: usize, y: usize) -> bool {
+        TriangleGrid::points_up(Position::new(x, y))
+    }
+
+    pub fn active_cells_ptr(&self) -> *const Position {
+        self.generator.active().as_ptr()
+    }
+    pub fn active_cells_len(&self) -> usize {
+        self.generator.active().len()
+    }
+
+    pub fn gen_step(&mut self) -> bool {
+        self.generator.gen_step()
+    }
+    pub fn generate(&mut self, limit: Option<usize>) -> bool {
+        self.generator.generate(limit)
+    }
+}
+impl TriangleMaze {
+    fn with_rng(width: usize, height: usize, mut rng: SmallRng, algorithm: Algorithm) -> Self {
+        let mut grid = TriangleGrid::new(width, height);
+        let algorithm = algorithm.build_generic(&mut grid, &mut rng, Position::new(0, 0));
+        Self {
+            generator: Generator::new(grid, rng, algorithm),
+        }
+    }
+
+    pub fn grid(&self) -> &TriangleGrid {
+        self.generator.grid()
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct TriangleGrid {
+    width: usize,
+    height: usize,
+
+    cells: Vec<TriangleCell>,
+}
+impl TriangleGrid {
+    pub fn new(width: usize, height: usize) -> Self {
+        let mut default_cell = TriangleCell::new();
+        default_cell.set_right(true);
+        default_cell.set_bottom(true);
+        Self {
+            width,
+            height,
+
+            cells: vec![default_cell; width * height],
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn points_up(pos: Position) -> bool {
+        (pos.x + pos.y) & 1 == 0
+    }
+
+    pub fn get_cell_offset(&self, x: usize, y: usize) -> usize {
+        x + y * self.width
+    }
+    pub fn get_cell(&self, x: usize, y: usize) -> TriangleCell {
+        self.cells[self.get_cell_offset(x, y)]
+    }
+    pub fn get_cell_mut(&mut self, x: usize, y: usize) -> &mut TriangleCell {
+        let offset = self.get_cell_offset(x, y);
+        &mut self.cells[offset]
+    }
+
+    /// Returns the position next to `pos` in the given direction, if it is inside the grid.
+    /// Cells pointing up only have a neighbour below them, and cells pointing down one above them.
+    pub fn neighbour(&self, pos: Position, dir: TriangleDirection) -> Option<Position> {
+        match dir {
+            TriangleDirection::Left if pos.x > 0 => Some(Position::new(pos.x - 1, pos.y)),
+            TriangleDirection::Right if pos.x + 1 < self.width => {
+                Some(Position::new(pos.x + 1, pos.y))
+            }
+            TriangleDirection::Up if !Self::points_up(pos) && pos.y > 0 => {
+                Some(Position::new(pos.x, pos.y - 1))
+            }
+            TriangleDirection::Down if Self::points_up(pos) && pos.y + 1 < self.height => {
+                Some(Position::new(pos.x, pos.y + 1))
+            }
+            _ => None,
+        }
+    }
+
+    /// Whether there is a wall between `pos` and its neighbour in the given direction,
+    /// the borders of the grid always count as walls
+    pub fn has_wall(&self, pos: Position, dir: TriangleDirection) -> bool {
+        let next = match self.neighbour(pos, dir) {
+            Some(next) => next,
+            None => return true,
+        };
+        match dir {
+            TriangleDirection::Right => self.get_cell(pos.x, pos.y).right(),
+            TriangleDirection::Left => self.get_cell(next.x, next.y).right(),
+            TriangleDirection::Down => self.get_cell(pos.x, pos.y).bottom(),
+            TriangleDirection::Up => self.get_cell(next.x, next.y).bottom(),
+        }
+    }
+
+    /// Adds or removes the wall between `pos` and its neighbour in the given direction
+    pub fn set_wall(&mut self, pos: Position, dir: TriangleDirection, wall: bool) {
+        let next = match self.neighbour(pos, dir) {
+            Some(next) => next,
+            None => return,
+        };
+        match dir {
+            TriangleDirection::Right => self.get_cell_mut(pos.x, pos.y).set_right(wall),
+            TriangleDirection::Left => self.get_cell_mut(next.x, next.y).set_right(wall),
+            TriangleDirection::Down => self.get_cell_mut(pos.x, pos.y).set_bottom(wall),
+            TriangleDirection::Up => self.get_cell_mut(next.x, next.y).set_bottom(wall),
+        }
+    }
+    /// Removes the wall between `pos` and its neighbour in the given direction
+    pub fn carve(&mut self, pos: Position, dir: TriangleDirection) {
+        self.set_wall(pos, dir, false);
+    }
+}
+impl Topology for TriangleGrid {
+    type Cell = Position;
+
+    fn len(&self) -> usize {
+        self.cells.len()
+    }
+    fn index(&self, cell: Position) -> usize {
+        self.get_cell_offset(cell.x, cell.y)
+    }
+    fn cell(&self, index: usize) -> Position {
+        Position::new(index % self.width, index / self.width)
+    }
+    fn neighbours(&self, cell: Position) -> Vec<Position> {
+        TriangleDirection::ALL
+            .iter()
+            .filter_map(|&dir| self.neighbour(cell, dir))
+            .collect()
+    }
+    fn link(&mut self, a: Position, b: Position) {
+        if let Some(&dir) = TriangleDirection::ALL
+            .iter()
+            .find(|&&dir| self.neighbour(a, dir) == Some(b))
+        {
+            self.carve(a, dir);
+        }
+    }
+
+    fn visited(&self, cell: Position) -> bool {
+        self.get_cell(cell.x, cell.y).visited()
+    }
+    fn set_visited(&mut self, cell: Position, visited: bool) {
+        self.get_cell_mut(cell.x, cell.y).set_visited(visited);
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TriangleDirection {
+    Left,
+    Right,
+    /// Through the top edge, only for cells pointing down
+    Up,
+    /// Through the bottom edge, only for cells pointing up
+    Down,
+}
+impl TriangleDirection {
+    pub const ALL: [TriangleDirection; 4] = [
+        TriangleDirection::Left,
+        TriangleDirection::Right,
+        TriangleDirection::Up,
+        TriangleDirection::Down,
+    ];
+
+    pub fn opposite(self) -> TriangleDirection {
+        match self {
+            TriangleDirection::Left => TriangleDirection::Right,
+            TriangleDirection::Right => TriangleDirection::Left,
+            TriangleDirection::Up => TriangleDirection::Down,
+            TriangleDirection::Down => TriangleDirection::Up,
+        }
+    }
+}
+
+bitfield! {
+    #[wasm_bindgen]
+    #[derive(Clone, Copy, Debug)]
+    pub struct TriangleCell(u8);
+    bool;
+    pub visited, set_visited: 0;
+    pub right, set_right: 1;
+    // Only used by the cells pointing up
+    pub bottom, set_bottom: 2;
+}
+impl TriangleCell {
+    pub fn new() -> Self {
+        TriangleCell(0)
+    }
+}
+impl Default for TriangleCell {
+    fn default() -> Self {
+        Self::new()
+    }
+}
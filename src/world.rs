@@ -0,0 +1,169 @@
+use crate::{Algorithm, GeneratorOptions, Maze, MazeCell, Position, Wrap};
+use rand::{rngs::SmallRng, seq::index, SeedableRng};
+use std::collections::HashMap;
+use wasm_bindgen::prelude::*;
+
+const CHUNK_SALT: u64 = 0;
+const EAST_SALT: u64 = 1;
+const SOUTH_SALT: u64 = 2;
+
+/// An endless maze split into chunks of the same size, each being a perfect `Maze` seeded from the
+/// world seed and its coordinates. The borders between chunks are opened at places that only depend
+/// on the seed and the coordinates of the border, so adjacent chunks always connect whatever the
+/// order they are loaded in, and unloaded chunks come back the same. The algorithm and options are
+/// adjusted by `Algorithm::perfect` so that every cell of a chunk is carved.
+///
+/// Like inside a `Maze`, the passages through a border are stored in the chunk on its left or top,
+/// as the right and bottom walls of its last column and row.
+#[wasm_bindgen]
+#[derive(Debug)]
+pub struct MazeWorld {
+    seed: u64,
+    chunk_width: usize,
+    chunk_height: usize,
+    algorithm: Algorithm,
+    options: GeneratorOptions,
+    /// Number of passages through each border
+    passages: usize,
+
+    chunks: HashMap<(i32, i32), Maze>,
+}
+#[wasm_bindgen]
+impl MazeWorld {
+    /// Creates an empty world whose borders between chunks have `passages` openings, at least one
+    pub fn new(
+        seed: u64,
+        chunk_width: usize,
+        chunk_height: usize,
+        algorithm: Algorithm,
+        options: &GeneratorOptions,
+        passages: usize,
+    ) -> Self {
+        let (algorithm, mut options) = algorithm.perfect(options);
+        options.wrap = Wrap::None;
+        Self {
+            seed,
+            chunk_width: chunk_width.max(1),
+            chunk_height: chunk_height.max(1),
+            algorithm,
+            options,
+            passages: passages.max(1),
+
+            chunks: HashMap::new(),
+        }
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+    pub fn chunk_width(&self) -> usize {
+        self.chunk_width
+    }
+    pub fn chunk_height(&self) -> usize {
+        self.chunk_height
+    }
+
+    /// Generates the chunk if it isn't loaded yet
+    pub fn load_chunk(&mut self, chunk_x: i32, chunk_y: i32) {
+        if !self.chunks.contains_key(&(chunk_x, chunk_y)) {
+            let chunk = self.generate_chunk(chunk_x, chunk_y);
+            self.chunks.insert((chunk_x, chunk_y), chunk);
+        }
+    }
+    /// Frees the chunk, returns false if it wasn't loaded
+    pub fn unload_chunk(&mut self, chunk_x: i32, chunk_y: i32) -> bool {
+        self.chunks.remove(&(chunk_x, chunk_y)).is_some()
+    }
+    pub fn is_loaded(&self, chunk_x: i32, chunk_y: i32) -> bool {
+        self.chunks.contains_key(&(chunk_x, chunk_y))
+    }
+    pub fn loaded_len(&self) -> usize {
+        self.chunks.len()
+    }
+
+    /// Pointer to the cells of the chunk, loading it if needed
+    pub fn chunk_cells_ptr(&mut self, chunk_x: i32, chunk_y: i32) -> *const MazeCell {
+        self.chunk(chunk_x, chunk_y).cells_ptr()
+    }
+    /// Cell at the given world coordinates, loading its chunk if needed
+    pub fn get_cell(&mut self, x: i32, y: i32) -> MazeCell {
+        let (chunk_x, chunk_y) = self.chunk_of(x, y);
+        let local_x = x.rem_euclid(self.chunk_width as i32) as usize;
+        let local_y = y.rem_euclid(self.chunk_height as i32) as usize;
+        self.chunk(chunk_x, chunk_y).get_cell(local_x, local_y)
+    }
+    /// Coordinates of the chunk containing the cell, as a pair of x and y
+    pub fn chunk_position(&self, x: i32, y: i32) -> Vec<i32> {
+        let (chunk_x, chunk_y) = self.chunk_of(x, y);
+        vec![chunk_x, chunk_y]
+    }
+
+    /// Rows of the openings between the chunk and the one on its right
+    pub fn east_passages(&self, chunk_x: i32, chunk_y: i32) -> Vec<usize> {
+        self.border_passages(chunk_x, chunk_y, EAST_SALT, self.chunk_height)
+    }
+    /// Columns of the openings between the chunk and the one below it
+    pub fn south_passages(&self, chunk_x: i32, chunk_y: i32) -> Vec<usize> {
+        self.border_passages(chunk_x, chunk_y, SOUTH_SALT, self.chunk_width)
+    }
+}
+impl MazeWorld {
+    /// The chunk, loading it if needed
+    pub fn chunk(&mut self, chunk_x: i32, chunk_y: i32) -> &Maze {
+        self.load_chunk(chunk_x, chunk_y);
+        &self.chunks[&(chunk_x, chunk_y)]
+    }
+    fn chunk_of(&self, x: i32, y: i32) -> (i32, i32) {
+        (
+            x.div_euclid(self.chunk_width as i32),
+            y.div_euclid(self.chunk_height as i32),
+        )
+    }
+
+    fn generate_chunk(&self, chunk_x: i32, chunk_y: i32) -> Maze {
+        let seed = hash(self.seed, chunk_x, chunk_y, CHUNK_SALT);
+        let mut chunk = Maze::from_seed_with_options(
+            self.chunk_width,
+            self.chunk_height,
+            seed,
+            self.algorithm,
+            &self.options,
+        );
+        chunk.generate(None);
+
+        let grid = chunk.grid_mut();
+        for y in self.east_passages(chunk_x, chunk_y) {
+            let pos = Position::new(self.chunk_width - 1, y);
+            grid.cell_mut(pos).set_right(false);
+        }
+        for x in self.south_passages(chunk_x, chunk_y) {
+            let pos = Position::new(x, self.chunk_height - 1);
+            grid.cell_mut(pos).set_bottom(false);
+        }
+        chunk
+    }
+
+    fn border_passages(&self, chunk_x: i32, chunk_y: i32, salt: u64, len: usize) -> Vec<usize> {
+        let mut rng = SmallRng::seed_from_u64(hash(self.seed, chunk_x, chunk_y, salt));
+        let mut passages = index::sample(&mut rng, len, self.passages.min(len)).into_vec();
+        passages.sort_unstable();
+        passages
+    }
+}
+
+/// Mixes the world seed with the coordinates of a chunk, so that neighbouring chunks get
+/// unrelated seeds
+fn hash(seed: u64, chunk_x: i32, chunk_y: i32, salt: u64) -> u64 {
+    let mut hash = seed ^ salt.wrapping_mul(0x9E37_79B9_7F4A_7C15);
+    for &value in [chunk_x, chunk_y].iter() {
+        hash = split_mix(hash ^ value as u32 as u64);
+    }
+    hash
+}
+/// Finalizer of the SplitMix64 generator
+fn split_mix(value: u64) -> u64 {
+    let mut z = value.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}